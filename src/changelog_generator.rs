@@ -0,0 +1,188 @@
+use std::collections::{BTreeMap, HashSet};
+use serde::Serialize;
+use tera::{Context, Tera};
+use crate::doc_generator::load_template;
+use crate::idl_parser::{referenced_types, Document, Service};
+use crate::ir_diff::{Change, ChangeKind, FileDiff};
+
+const TEMPLATE: &str = "changelog_template.md";
+
+/// 模板中的一行变化
+#[derive(Serialize)]
+struct Row {
+    kind: &'static str,
+    target: &'static str,
+    path: String,
+    before: Option<String>,
+    after: Option<String>,
+    breaking: bool,
+}
+
+#[derive(Serialize)]
+struct ServiceSection {
+    name: String,
+    changes: Vec<Row>,
+}
+
+#[derive(Serialize)]
+struct FileSection {
+    name: String,
+    services: Vec<ServiceSection>,
+    /// 没有被任何服务引用的类型的变化
+    types: Vec<Row>,
+}
+
+/// 根据比较结果生成 Markdown 格式的 API 变更记录
+///
+/// 变化按服务分组：服务和方法的变化归入所属服务，类型的变化归入（新旧版本中）引用了该类型的每个服务，
+/// 没有被服务引用的类型单独列出。
+pub fn generate_changelog(diffs: &[FileDiff], old_label: &str, new_label: &str) -> String {
+    let files: Vec<FileSection> = diffs.iter().filter(|d| !d.changes.is_empty()).map(file_section).collect();
+    let changes = diffs.iter().flat_map(|d| &d.changes);
+
+    let mut context = Context::new();
+    context.insert("old", old_label);
+    context.insert("new", new_label);
+    context.insert("total", &changes.clone().count());
+    context.insert("breaking", &changes.filter(|c| c.breaking).count());
+    context.insert("files", &files);
+
+    let mut tera = Tera::default();
+    tera.add_raw_template(TEMPLATE, &load_template(TEMPLATE)).expect("添加模板失败");
+    tera.render(TEMPLATE, &context).expect("渲染变更记录模板失败")
+}
+
+fn file_section(diff: &FileDiff) -> FileSection {
+    let mut usage = type_usage(&diff.old);
+    for (ty, services) in type_usage(&diff.new) {
+        usage.entry(ty).or_default().extend(services);
+    }
+
+    let mut services: BTreeMap<String, Vec<Row>> = BTreeMap::new();
+    let mut types = Vec::new();
+    for change in &diff.changes {
+        match change.target {
+            // 服务改名记录在新名称下
+            "service" if change.kind == ChangeKind::Modified => {
+                let name = change.after.clone().unwrap_or_else(|| change.owner.clone());
+                services.entry(name).or_default().push(row(change));
+            }
+            "service" | "method" => services.entry(change.owner.clone()).or_default().push(row(change)),
+            // 参数的 owner 为 `服务.方法`
+            "argument" => {
+                let service = change.owner.split_once('.').map_or(change.owner.as_str(), |(service, _)| service);
+                services.entry(service.to_string()).or_default().push(row(change));
+            }
+            _ => match usage.get(&change.owner) {
+                Some(users) => {
+                    for service in users {
+                        services.entry(service.clone()).or_default().push(row(change));
+                    }
+                }
+                None => types.push(row(change)),
+            },
+        }
+    }
+
+    FileSection {
+        name: diff.name.clone(),
+        services: services.into_iter().map(|(name, changes)| ServiceSection { name, changes }).collect(),
+        types,
+    }
+}
+
+fn row(change: &Change) -> Row {
+    Row {
+        kind: match change.kind {
+            ChangeKind::Added => "新增",
+            ChangeKind::Removed => "删除",
+            ChangeKind::Modified => "修改",
+        },
+        target: match change.target {
+            "service" => "服务",
+            "method" => "方法",
+            "argument" => "参数",
+            "struct" => "结构体",
+            "field" => "字段",
+            "enum" => "枚举",
+            "enum_value" => "枚举项",
+            _ => "typedef",
+        },
+        path: change.path(),
+        before: change.before.clone(),
+        after: change.after.clone(),
+        breaking: change.breaking,
+    }
+}
+
+/// 每个类型被哪些服务（通过参数、返回值、异常递归）引用，key 为类型名
+fn type_usage(doc: &Document) -> BTreeMap<String, HashSet<String>> {
+    let mut usage: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+    for service in &doc.services {
+        for ty in service_types(doc, service) {
+            usage.entry(ty).or_default().insert(service.name.clone());
+        }
+    }
+    usage
+}
+
+fn service_types(doc: &Document, service: &Service) -> HashSet<String> {
+    let mut pending: Vec<String> = Vec::new();
+    for method in &service.methods {
+        pending.extend(referenced_types(&method.request.name));
+        pending.extend(referenced_types(&method.response.name));
+        pending.extend(method.throws.iter().flat_map(|e| referenced_types(&e.r#type)));
+    }
+    let mut types = HashSet::new();
+    while let Some(ty) = pending.pop() {
+        if !types.insert(ty.clone()) {
+            continue;
+        }
+        if let Some(s) = doc.structs.iter().find(|s| s.name == ty) {
+            pending.extend(s.fields.iter().flat_map(|f| referenced_types(&f.r#type)));
+        }
+        if let Some(t) = doc.typedefs.iter().find(|t| t.name == ty) {
+            pending.extend(referenced_types(&t.r#type));
+        }
+    }
+    types
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl_parser::{build_document, parse_idl};
+    use crate::ir_diff::diff_documents;
+
+    #[test]
+    fn test_generate_changelog() {
+        let document = |content: &str| build_document(&parse_idl(content).unwrap());
+        let old = document(
+            r#"
+            struct Item { 1: required i64 id }
+            struct Unused { 1: string name }
+            service ItemService { Item GetItem (1: Item req) }
+            "#,
+        );
+        let new = document(
+            r#"
+            struct Item { 1: required string id, 2: optional string title }
+            service ItemService {
+                Item GetItem (1: Item req),
+                void Ping (),
+            }
+            "#,
+        );
+        let changes = diff_documents(&old, &new);
+        let diff = FileDiff { name: "item.thrift".to_string(), old, new, changes };
+        let changelog = generate_changelog(&[diff], "v1", "v2");
+
+        assert!(changelog.contains("共 4 处变化，其中 2 处不兼容"));
+        assert!(changelog.contains("## item.thrift"));
+        assert!(changelog.contains("### 服务 ItemService"));
+        assert!(changelog.contains("| 新增 | 方法 | `ItemService.Ping` |  | `void Ping()` | 兼容 |"));
+        assert!(changelog.contains("| 修改 | 字段 | `Item.id` | `1: required i64 id` | `1: required string id` | **不兼容** |"));
+        assert!(changelog.contains("### 其它类型"));
+        assert!(changelog.contains("| 删除 | 结构体 | `Unused` | `struct` |  | **不兼容** |"));
+    }
+}
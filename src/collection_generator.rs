@@ -0,0 +1,276 @@
+use serde_json::{json, Map, Value};
+use crate::config::CollectionConfig;
+use crate::idl_parser::{Document, Method};
+use crate::openapi_generator::{bind_fields, has_http_annotation, http_route};
+
+const POSTMAN_SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// 方法对应的一次 HTTP 请求，Postman 与 Insomnia 共用
+struct Request {
+    http_method: String,
+    /// 路径，路径参数保留 `:id` 写法
+    path: String,
+    /// 路径参数的示例值
+    path_params: Vec<(String, String)>,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+}
+
+/// 根据方法的 HTTP 注解和示例数据整理出请求，规则与 OpenAPI 导出一致
+fn build_request(service: &str, method: &Method, sample: &Value) -> Request {
+    let (http_method, path) = http_route(service, method);
+    let mut request = Request {
+        http_method: http_method.to_uppercase(),
+        path,
+        path_params: Vec::new(),
+        query: Vec::new(),
+        headers: Vec::new(),
+        body: None,
+    };
+    if method.request.name.is_empty() {
+        return request;
+    }
+    if !has_http_annotation(method) {
+        request.body = Some(serde_json::to_string_pretty(sample).unwrap());
+        return request;
+    }
+
+    let mut body = Map::new();
+    for binding in bind_fields(method, &http_method) {
+        let value = sample.get(&binding.field.name).cloned().unwrap_or(Value::Null);
+        match binding.location {
+            "body" => {
+                body.insert(binding.name, value);
+            }
+            "path" => request.path_params.push((binding.name, param_value(&value))),
+            "query" => request.query.push((binding.name, param_value(&value))),
+            // cookie 参数也通过请求头传递
+            "cookie" => request.headers.push(("Cookie".to_string(), format!("{}={}", binding.name, param_value(&value)))),
+            _ => request.headers.push((binding.name, param_value(&value))),
+        }
+    }
+    if !body.is_empty() {
+        request.body = Some(serde_json::to_string_pretty(&body).unwrap());
+    }
+    request
+}
+
+/// 参数值转换为字符串，字符串不带引号
+fn param_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// 生成 Postman v2.1 collection：每个服务一个文件夹，每个方法一个请求
+///
+/// `samples` 为 `sample_data_generator::generate_document_samples` 由同一 Document 生成的示例数据，用于预填请求体和参数。
+/// 服务地址和公共请求头作为 collection 变量，请求中通过 `{{baseUrl}}`、`{{请求头名}}` 引用。
+pub fn generate_postman(doc: &Document, title: &str, samples: &Value, config: &CollectionConfig) -> Value {
+    let folders: Vec<Value> = doc
+        .services
+        .iter()
+        .map(|service| {
+            let items: Vec<Value> = service
+                .methods
+                .iter()
+                .map(|method| {
+                    let sample = &samples[&service.name][&method.name]["request"];
+                    let mut item = json!({
+                        "name": method.name,
+                        "request": postman_request(&build_request(&service.name, method, sample), config),
+                    });
+                    if !method.comment.is_empty() {
+                        item["request"]["description"] = json!(method.comment);
+                    }
+                    item
+                })
+                .collect();
+            let mut folder = json!({ "name": service.name, "item": items });
+            if !service.comment.is_empty() {
+                folder["description"] = json!(service.comment);
+            }
+            folder
+        })
+        .collect();
+
+    let mut variables = vec![json!({ "key": "baseUrl", "value": config.base_url })];
+    variables.extend(config.headers.iter().map(|(name, value)| json!({ "key": name, "value": value })));
+    json!({
+        "info": { "name": title, "schema": POSTMAN_SCHEMA },
+        "item": folders,
+        "variable": variables,
+    })
+}
+
+fn postman_request(request: &Request, config: &CollectionConfig) -> Value {
+    let mut headers = Vec::new();
+    if request.body.is_some() {
+        headers.push(json!({ "key": "Content-Type", "value": "application/json" }));
+    }
+    headers.extend(config.headers.keys().map(|name| json!({ "key": name, "value": format!("{{{{{}}}}}", name) })));
+    headers.extend(request.headers.iter().map(|(key, value)| json!({ "key": key, "value": value })));
+
+    let mut raw = format!("{{{{baseUrl}}}}{}", request.path);
+    if !request.query.is_empty() {
+        let query: Vec<String> = request.query.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        raw = format!("{}?{}", raw, query.join("&"));
+    }
+    let mut url = json!({
+        "raw": raw,
+        "host": ["{{baseUrl}}"],
+        "path": request.path.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>(),
+    });
+    if !request.query.is_empty() {
+        url["query"] = json!(request.query.iter().map(|(k, v)| json!({ "key": k, "value": v })).collect::<Vec<_>>());
+    }
+    if !request.path_params.is_empty() {
+        url["variable"] = json!(request.path_params.iter().map(|(k, v)| json!({ "key": k, "value": v })).collect::<Vec<_>>());
+    }
+
+    let mut value = json!({ "method": request.http_method, "header": headers, "url": url });
+    if let Some(body) = &request.body {
+        value["body"] = json!({ "mode": "raw", "raw": body, "options": { "raw": { "language": "json" } } });
+    }
+    value
+}
+
+/// 生成 Insomnia v4 导出文件：服务对应请求分组，服务地址和公共请求头放在基础环境中
+pub fn generate_insomnia(doc: &Document, title: &str, samples: &Value, config: &CollectionConfig) -> Value {
+    let workspace_id = "wrk_volodoc";
+    let mut env = Map::new();
+    env.insert("baseUrl".to_string(), json!(config.base_url));
+    for (name, value) in &config.headers {
+        env.insert(name.clone(), json!(value));
+    }
+    let mut resources = vec![
+        json!({ "_id": workspace_id, "_type": "workspace", "name": title }),
+        json!({ "_id": "env_volodoc", "_type": "environment", "parentId": workspace_id, "name": "Base Environment", "data": env }),
+    ];
+
+    for service in &doc.services {
+        let group_id = format!("fld_{}", service.name);
+        resources.push(json!({
+            "_id": group_id,
+            "_type": "request_group",
+            "parentId": workspace_id,
+            "name": service.name,
+            "description": service.comment,
+        }));
+        for method in &service.methods {
+            let sample = &samples[&service.name][&method.name]["request"];
+            let request = build_request(&service.name, method, sample);
+            // Insomnia 不支持路径变量，直接代入示例值
+            let path = fill_path_params(&request.path, &request.path_params);
+
+            let mut headers = Vec::new();
+            if request.body.is_some() {
+                headers.push(json!({ "name": "Content-Type", "value": "application/json" }));
+            }
+            headers.extend(config.headers.keys().map(|name| json!({ "name": name, "value": format!("{{{{ _.{} }}}}", name) })));
+            headers.extend(request.headers.iter().map(|(name, value)| json!({ "name": name, "value": value })));
+
+            let body = match &request.body {
+                Some(text) => json!({ "mimeType": "application/json", "text": text }),
+                None => json!({}),
+            };
+            resources.push(json!({
+                "_id": format!("req_{}_{}", service.name, method.name),
+                "_type": "request",
+                "parentId": group_id,
+                "name": method.name,
+                "description": method.comment,
+                "method": request.http_method,
+                "url": format!("{{{{ _.baseUrl }}}}{}", path),
+                "parameters": request.query.iter().map(|(k, v)| json!({ "name": k, "value": v })).collect::<Vec<_>>(),
+                "headers": headers,
+                "body": body,
+            }));
+        }
+    }
+
+    json!({
+        "_type": "export",
+        "__export_format": 4,
+        "__export_source": format!("volodoc:{}", env!("CARGO_PKG_VERSION")),
+        "resources": resources,
+    })
+}
+
+/// 把路径中的参数段（`:name` 或 `{name}`）替换为示例值，只替换整段都是参数名的路径段
+fn fill_path_params(path: &str, params: &[(String, String)]) -> String {
+    path.split('/')
+        .map(|segment| {
+            let name = segment
+                .strip_prefix(':')
+                .or_else(|| segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')));
+            name.and_then(|name| params.iter().find(|(k, _)| k == name))
+                .map_or(segment, |(_, v)| v.as_str())
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use crate::idl_parser::{build_document, parse_idl};
+    use crate::sample_data_generator::generate_sample_values;
+
+    const SAMPLE_THRIFT: &str = r#"
+        struct GetItemRequest {
+            1: required i64 id (api.path = 'id'),
+            2: optional string lang (api.query = 'lang'),
+        }
+        struct Item { 1: required i64 id }
+        service ItemService {
+            Item GetItem (1: GetItemRequest req) (api.get = '/item/:id'),
+            Item SaveItem (1: Item item),
+        }
+    "#;
+
+    #[test]
+    fn test_generate_collections() {
+        let file = parse_idl(SAMPLE_THRIFT).unwrap();
+        let doc = build_document(&file);
+        let samples = generate_sample_values(&file);
+        let config = CollectionConfig {
+            base_url: "http://localhost:8888".to_string(),
+            headers: BTreeMap::from([("Authorization".to_string(), "Bearer token".to_string())]),
+        };
+
+        let postman = generate_postman(&doc, "item", &samples, &config);
+        assert_eq!(postman["info"]["schema"], POSTMAN_SCHEMA);
+        assert_eq!(postman["variable"][0]["value"], "http://localhost:8888");
+        assert_eq!(postman["variable"][1]["key"], "Authorization");
+        let folder = &postman["item"][0];
+        assert_eq!(folder["name"], "ItemService");
+        let get = &folder["item"][0]["request"];
+        assert_eq!(get["method"], "GET");
+        assert_eq!(get["url"]["path"], json!(["item", ":id"]));
+        assert_eq!(get["url"]["variable"][0]["key"], "id");
+        assert_eq!(get["url"]["query"][0]["key"], "lang");
+        assert_eq!(get["header"][0]["value"], "{{Authorization}}");
+        assert!(get.get("body").is_none());
+        let save = &folder["item"][1]["request"];
+        assert_eq!(save["method"], "POST");
+        assert_eq!(save["url"]["raw"], "{{baseUrl}}/ItemService/SaveItem");
+        assert_eq!(save["body"]["mode"], "raw");
+
+        let insomnia = generate_insomnia(&doc, "item", &samples, &config);
+        let resources = insomnia["resources"].as_array().unwrap();
+        assert_eq!(resources[1]["data"]["baseUrl"], "http://localhost:8888");
+        assert_eq!(resources[2]["_type"], "request_group");
+        assert_eq!(resources[3]["parentId"], "fld_ItemService");
+        assert!(!resources[3]["url"].as_str().unwrap().contains(":id"));
+
+        // 只替换整段匹配的参数，`:id` 不会影响 `:idx`
+        let params = [("id".to_string(), "1".to_string()), ("idx".to_string(), "2".to_string())];
+        assert_eq!(fill_path_params("/item/:idx/:id", &params), "/item/2/1");
+        assert_eq!(fill_path_params("/item/{id}/x:id", &params), "/item/1/x:id");
+    }
+}
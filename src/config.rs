@@ -0,0 +1,264 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use serde::Deserialize;
+
+/// 配置文件名，位于执行 volodoc 的目录下
+pub const CONFIG_FILE: &str = "volodoc.toml";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// volodoc.toml 中的配置项，所有字段均有默认值
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// 需要扫描的 IDL 目录
+    pub idl_roots: Vec<String>,
+    /// 自定义模板目录，其中存在同名模板时优先于内置模板
+    pub template_dir: Option<String>,
+    /// 是否在文档中生成 Mermaid 关系图
+    pub diagrams: bool,
+    /// HTML 文档中渲染关系图使用的 mermaid 脚本地址（ES module），例如 CDN 地址；
+    /// 不设置时页面不加载任何外部资源，关系图以 Mermaid 源码文本显示
+    pub mermaid_url: Option<String>,
+    pub serve: ServeConfig,
+    pub typescript: TypeScriptConfig,
+    pub collection: CollectionConfig,
+    pub graphql: GraphQLConfig,
+    pub sample: SampleConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            idl_roots: vec!["idl".to_string()],
+            template_dir: None,
+            diagrams: false,
+            mermaid_url: None,
+            serve: ServeConfig::default(),
+            typescript: TypeScriptConfig::default(),
+            collection: CollectionConfig::default(),
+            graphql: GraphQLConfig::default(),
+            sample: SampleConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// 列出所有 IDL 目录下以 .thrift 为后缀的文件，按路径排序保证输出稳定
+    pub fn idl_files(&self) -> Result<Vec<String>, String> {
+        let mut paths = Vec::new();
+        for root in &self.idl_roots {
+            paths.extend(thrift_files(root)?);
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// 检查取值有限的配置项，命令行选项覆盖配置后调用
+    pub fn validate(&self) -> Result<(), String> {
+        if !matches!(self.sample.i64.as_str(), "number" | "string") {
+            return Err(format!("不支持的 sample.i64：{}（可选 number、string）", self.sample.i64));
+        }
+        if self.sample.max_depth == 0 {
+            return Err("sample.max_depth 至少为 1".to_string());
+        }
+        if self.sample.list_min > self.sample.list_max {
+            return Err(format!(
+                "sample.list_min（{}）不能大于 sample.list_max（{}）",
+                self.sample.list_min, self.sample.list_max
+            ));
+        }
+        if !matches!(self.sample.optional.as_str(), "omit" | "null" | "fill" | "both") {
+            return Err(format!(
+                "不支持的 sample.optional：{}（可选 omit、null、fill、both）",
+                self.sample.optional
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 列出目录下（不递归）以 .thrift 为后缀的文件
+pub fn thrift_files(dir: &str) -> Result<Vec<String>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("读取 {} 目录失败: {}", dir, e))?;
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| format!("读取目录项失败: {}", e))?.path();
+        if path.extension().is_some_and(|ext| ext == "thrift") {
+            paths.push(path.to_string_lossy().to_string());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// `volodoc serve` 相关配置
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ServeConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Default for ServeConfig {
+    fn default() -> Self {
+        ServeConfig {
+            host: "127.0.0.1".to_string(),
+            port: 3000,
+        }
+    }
+}
+
+/// `volodoc export typescript` 相关配置
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct TypeScriptConfig {
+    /// 枚举的生成方式：numeric（TypeScript enum，值为整数）或 string（名称组成的字符串字面量联合类型）
+    pub enum_style: String,
+    /// i64 对应的 TypeScript 类型：number、bigint 或 string
+    pub i64: String,
+}
+
+impl Default for TypeScriptConfig {
+    fn default() -> Self {
+        TypeScriptConfig {
+            enum_style: "numeric".to_string(),
+            i64: "number".to_string(),
+        }
+    }
+}
+
+/// `volodoc export postman` / `volodoc export insomnia` 相关配置
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct CollectionConfig {
+    /// 服务地址，生成为 baseUrl 变量
+    pub base_url: String,
+    /// 每个请求都携带的公共请求头，每个请求头生成一个同名变量
+    pub headers: BTreeMap<String, String>,
+}
+
+impl Default for CollectionConfig {
+    fn default() -> Self {
+        CollectionConfig {
+            base_url: "http://127.0.0.1:8080".to_string(),
+            headers: BTreeMap::new(),
+        }
+    }
+}
+
+/// `volodoc export graphql` 相关配置
+///
+/// 方法带有 `graphql.query` 或 `graphql.mutation` 注解时以注解为准，其次查找 `queries`、`mutations`
+/// 中列出的方法（`服务名.方法名`），都没有时方法名以 `query_prefixes` 中的前缀开头的作为 Query，其余作为 Mutation。
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct GraphQLConfig {
+    pub queries: Vec<String>,
+    pub mutations: Vec<String>,
+    pub query_prefixes: Vec<String>,
+}
+
+impl Default for GraphQLConfig {
+    fn default() -> Self {
+        GraphQLConfig {
+            queries: Vec::new(),
+            mutations: Vec::new(),
+            query_prefixes: ["Get", "List", "Query", "Search", "Find", "Count", "Check"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// 示例数据（`_test.md`、OpenAPI example 等）相关配置
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SampleConfig {
+    /// i64 在 JSON 中的表示方式：number（数字）或 string（字符串，避免 JavaScript 丢失精度）
+    pub i64: String,
+    /// 递归引用的结构体在同一条展开路径上最多展开的层数
+    pub max_depth: usize,
+    /// 可选字段的生成方式：omit（省略）、null、fill（生成实际示例数据）或 both（每个方法分别生成省略和填充可选字段的示例）
+    pub optional: String,
+    /// 按字段名生成示例值的规则，优先于内置规则
+    pub rules: Vec<SampleRule>,
+    /// 随机种子，设置后生成随机的示例值，相同的种子总是生成相同的结果
+    pub seed: Option<u64>,
+    /// 列表、set、map 的最少元素个数，固定模式（未设置 seed）下即为元素个数
+    pub list_min: usize,
+    /// 随机模式下列表、set、map 的最多元素个数
+    pub list_max: usize,
+}
+
+/// 按字段名生成示例值的规则，例如 `{ field = "*_email", value = "dev@example.com" }`
+#[derive(Debug, Deserialize)]
+pub struct SampleRule {
+    /// 字段名，`*` 匹配任意个字符，不区分大小写，驼峰命名的字段按下划线命名匹配（`createdAt` 按 `created_at` 匹配）
+    pub field: String,
+    /// 示例值，只用于类型相符的字段：字符串用于 string，整数用于整数类型（需在范围内）和 double，小数用于 double，布尔值用于 bool
+    pub value: serde_json::Value,
+}
+
+impl Default for SampleConfig {
+    fn default() -> Self {
+        SampleConfig {
+            i64: "number".to_string(),
+            max_depth: 3,
+            optional: "null".to_string(),
+            rules: Vec::new(),
+            seed: None,
+            list_min: 1,
+            list_max: 3,
+        }
+    }
+}
+
+/// 读取配置文件，文件不存在时返回默认配置
+pub fn load_config(path: &str) -> Result<Config, String> {
+    if !Path::new(path).exists() {
+        return Ok(Config::default());
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("读取配置文件 {} 失败: {}", path, e))?;
+    toml::from_str(&content).map_err(|e| format!("解析配置文件 {} 失败: {}", path, e))
+}
+
+/// 设置全局配置，只在程序启动时调用一次
+pub fn init(config: Config) {
+    let _ = CONFIG.set(config);
+}
+
+/// 获取全局配置，未初始化时使用默认配置
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = toml::from_str(
+            r#"
+            idl_roots = ["idl", "third_party/idl"]
+            template_dir = "templates"
+
+            [serve]
+            port = 8080
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.idl_roots, vec!["idl", "third_party/idl"]);
+        assert_eq!(config.template_dir.as_deref(), Some("templates"));
+        assert_eq!(config.serve.host, "127.0.0.1");
+        assert_eq!(config.serve.port, 8080);
+        assert!(config.validate().is_ok());
+
+        let config: Config = toml::from_str("[sample]\ni64 = \"bigint\"").unwrap();
+        assert!(config.validate().is_err());
+    }
+}
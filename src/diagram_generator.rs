@@ -0,0 +1,162 @@
+use serde::Serialize;
+use crate::idl_parser::{referenced_types, Enum, Service, Struct};
+
+/// 一个文件的全部 Mermaid 图，供 Markdown 与 HTML 模板使用
+#[derive(Debug, Serialize)]
+pub struct Diagrams {
+    /// 结构体、枚举及字段引用关系的类图
+    pub types: String,
+    /// 每个服务一张图
+    pub services: Vec<ServiceDiagram>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServiceDiagram {
+    pub name: String,
+    pub diagram: String,
+}
+
+/// 根据结构体、枚举和服务生成全部关系图
+pub fn generate_diagrams(structs: &[Struct], enums: &[Enum], services: &[Service]) -> Diagrams {
+    Diagrams {
+        types: class_diagram(structs, enums),
+        services: services
+            .iter()
+            .map(|s| ServiceDiagram {
+                name: s.name.clone(),
+                diagram: service_diagram(s),
+            })
+            .collect(),
+    }
+}
+
+/// 生成类图：每个结构体、枚举一个类，字段引用其它本地类型时画一条关联线
+pub fn class_diagram(structs: &[Struct], enums: &[Enum]) -> String {
+    let local: Vec<&str> = structs
+        .iter()
+        .map(|s| s.name.as_str())
+        .chain(enums.iter().map(|e| e.name.as_str()))
+        .collect();
+
+    let mut lines = vec!["classDiagram".to_string()];
+    for s in structs {
+        lines.push(format!("    class {} {{", node_id(&s.name)));
+        lines.push(format!("        <<{}>>", s.kind));
+        for f in &s.fields {
+            lines.push(format!("        +{} {}", mermaid_type(&f.r#type), f.name));
+        }
+        lines.push("    }".to_string());
+    }
+    for e in enums {
+        lines.push(format!("    class {} {{", node_id(&e.name)));
+        lines.push("        <<enumeration>>".to_string());
+        for v in &e.values {
+            lines.push(format!("        {}", v.name));
+        }
+        lines.push("    }".to_string());
+    }
+    for s in structs {
+        for f in &s.fields {
+            for name in referenced_types(&f.r#type) {
+                if local.contains(&name.as_str()) {
+                    lines.push(format!("    {} --> {} : {}", node_id(&s.name), node_id(&name), f.name));
+                }
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+/// 生成服务图：服务 -> 方法 -> 请求 / 返回 / 异常类型
+pub fn service_diagram(service: &Service) -> String {
+    let service_id = node_id(&service.name);
+    let mut lines = vec![
+        "flowchart LR".to_string(),
+        format!("    {}[[\"{}\"]]", service_id, service.name),
+    ];
+    for method in &service.methods {
+        let method_id = format!("{}_{}", service_id, node_id(&method.name));
+        lines.push(format!("    {} --> {}(\"{}\")", service_id, method_id, method.name));
+        if !method.request.name.is_empty() {
+            lines.push(format!(
+                "    {} -- request --> {}[\"{}\"]",
+                method_id,
+                type_node_id(&method.request.name),
+                method.request.name
+            ));
+        }
+        if !method.response.name.is_empty() && method.response.name != "void" {
+            lines.push(format!(
+                "    {} -- response --> {}[\"{}\"]",
+                method_id,
+                type_node_id(&method.response.name),
+                method.response.name
+            ));
+        }
+        for e in &method.throws {
+            lines.push(format!(
+                "    {} -. throws .-> {}[\"{}\"]",
+                method_id,
+                type_node_id(&e.r#type),
+                e.r#type
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Mermaid 类图使用 `~` 表示泛型，例如 `list<Item>` 写作 `list~Item~`
+fn mermaid_type(ty: &str) -> String {
+    ty.replace(['<', '>'], "~").replace(", ", ",")
+}
+
+/// 将名称转换为合法的 Mermaid 节点 ID（只保留字母、数字和下划线）
+fn node_id(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// 类型节点加上前缀，避免与服务、方法节点重名
+fn type_node_id(name: &str) -> String {
+    format!("type_{}", node_id(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl_parser::{build_document, parse_idl};
+
+    #[test]
+    fn test_generate_diagrams() {
+        let file = parse_idl(
+            r#"
+            enum Status { ON = 1, OFF = 2 }
+            struct Item {
+                1: required i64 id,
+                2: optional list<Item> children,
+                3: required Status status,
+            }
+            exception NotFound { 1: string message }
+            service ItemService {
+                Item GetItem (1: Item req) throws (1: NotFound e),
+            }
+            "#,
+        )
+        .unwrap();
+        let doc = build_document(&file);
+        let diagrams = generate_diagrams(&doc.structs, &doc.enums, &doc.services);
+
+        assert!(diagrams.types.starts_with("classDiagram"));
+        assert!(diagrams.types.contains("        +list~Item~ children"));
+        assert!(diagrams.types.contains("        <<enumeration>>"));
+        assert!(diagrams.types.contains("    Item --> Item : children"));
+        assert!(diagrams.types.contains("    Item --> Status : status"));
+
+        assert_eq!(diagrams.services[0].name, "ItemService");
+        let service = &diagrams.services[0].diagram;
+        assert!(service.contains("ItemService --> ItemService_GetItem(\"GetItem\")"));
+        assert!(service.contains("ItemService_GetItem -- request --> type_Item[\"Item\"]"));
+        assert!(service.contains("ItemService_GetItem -. throws .-> type_NotFound[\"NotFound\"]"));
+    }
+}
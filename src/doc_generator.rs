@@ -1,7 +1,11 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use serde_json::Value;
 use tera::{Context, Tera};
-use crate::idl_parser::{Service, Struct};
+use crate::config;
+use crate::diagram_generator::generate_diagrams;
+use crate::idl_parser::{collect_enums, collect_includes, namespace_of, referenced_types, Constraints, Service, Struct};
 use pilota_thrift_parser::File as DocFile;
 
 // 引入 rust-embed，用于嵌入模板文件
@@ -9,7 +13,7 @@ use rust_embed::RustEmbed;
 
 #[derive(RustEmbed)]
 #[folder = "src/templates/"]
-struct Asset;
+pub struct Asset;
 
 /// 生成 API 文档（包含结构体和服务信息）并保存到 result 文件夹中，然后返回 Markdown 字符串
 pub fn doc_handler(file: &DocFile, structs: &Vec<Struct>, services: &Vec<Service>) -> String {
@@ -20,21 +24,182 @@ pub fn doc_handler(file: &DocFile, structs: &Vec<Struct>, services: &Vec<Service
 }
 
 /// 根据 file、structs、services 数据渲染模板并返回生成的 API Markdown 文档
-/// 该模板中同时包含结构体和服务部分
-pub fn generate_api_doc(_file: &DocFile, structs: &Vec<Struct>, services: &Vec<Service>) -> String {
-    // 从嵌入的资源中加载模板内容
-    let template_data = Asset::get("api_template.md").expect("找不到 api_template.md 模板");
-    let template_str = std::str::from_utf8(template_data.data.as_ref()).expect("模板内容不是有效 utf8");
+/// 该模板中同时包含目录、结构体、枚举和服务部分，类型引用会链接到对应的定义
+pub fn generate_api_doc(file: &DocFile, structs: &Vec<Struct>, services: &Vec<Service>) -> String {
+    // 加载模板内容（自定义模板优先，否则使用嵌入的资源）
+    let template_str = load_template("api_template.md");
 
     // 创建一个 Tera 实例并手动添加模板
     let mut tera = Tera::default();
-    tera.add_raw_template("api_template.md", template_str).expect("添加模板失败");
+    tera.add_raw_template("api_template.md", &template_str).expect("添加模板失败");
+
+    let enums = collect_enums(file);
+    let mut local_types: Vec<&str> = structs.iter().map(|s| s.name.as_str()).collect();
+    local_types.extend(enums.iter().map(|e| e.name.as_str()));
+    let targets = link_targets(&local_types, &collect_includes(file));
+    tera.register_filter(
+        "type_link",
+        move |value: &Value, _: &HashMap<String, Value>| -> tera::Result<Value> {
+            let ty = tera::try_get_value!("type_link", "value", String, value);
+            Ok(Value::String(link_type(&ty, &targets)))
+        },
+    );
+    tera.register_filter("constraints", constraints_filter);
 
     // 构造上下文数据
     let mut context = Context::new();
-    context.insert("package", "rs.volo.example");
+    context.insert("package", &namespace_of(file));
     context.insert("structs", structs);
+    context.insert("enums", &enums);
     context.insert("services", services);
+    context.insert("used_by", &used_by(&local_types, structs, services));
+    if config::get().diagrams {
+        context.insert("diagrams", &generate_diagrams(structs, &enums, services));
+    } else {
+        context.insert("diagrams", &false);
+    }
 
     tera.render("api_template.md", &context).expect("渲染 API 模板失败")
-}
\ No newline at end of file
+}
+
+/// 模板过滤器：将字段的 `constraints` 转换为约束说明文字
+pub fn constraints_filter(value: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+    let constraints = tera::try_get_value!("constraints", "value", Constraints, value);
+    Ok(Value::String(constraints.describe()))
+}
+
+/// 收集可以链接的类型：本文件的类型链接到页内锚点，
+/// include 进来的类型（写法为 `文件名.类型名`）链接到对应文件生成的 `{文件名}_api.md`
+fn link_targets(local_types: &[&str], includes: &[String]) -> HashMap<String, String> {
+    let mut targets: HashMap<String, String> = local_types
+        .iter()
+        .map(|name| (name.to_string(), format!("#type-{}", name)))
+        .collect();
+    for include in includes {
+        if let Some(stem) = Path::new(include).file_stem().and_then(|s| s.to_str()) {
+            targets.insert(format!("{}.", stem), format!("{}_api.md#type-", stem));
+        }
+    }
+    targets
+}
+
+/// 将类型字符串中的自定义类型替换为 Markdown 链接，例如 `list<Item>` 转换为 `list\<[Item](#type-Item)\>`
+/// 尖括号需要转义，否则会被当作 HTML 标签
+fn link_type(ty: &str, targets: &HashMap<String, String>) -> String {
+    let mut md = String::new();
+    let mut token = String::new();
+    let flush = |token: &mut String, md: &mut String| {
+        if token.is_empty() {
+            return;
+        }
+        if let Some(href) = targets.get(token.as_str()) {
+            md.push_str(&format!("[{}]({})", token, href));
+        } else if let Some((prefix, name)) = token.split_once('.')
+            && let Some(href) = targets.get(&format!("{}.", prefix))
+        {
+            md.push_str(&format!("[{}]({}{})", token, href, name));
+        } else {
+            md.push_str(token);
+        }
+        token.clear();
+    };
+    for c in ty.chars() {
+        if c.is_alphanumeric() || c == '_' || c == '.' {
+            token.push(c);
+        } else {
+            flush(&mut token, &mut md);
+            if c == '<' || c == '>' {
+                md.push('\\');
+            }
+            md.push(c);
+        }
+    }
+    flush(&mut token, &mut md);
+    md
+}
+
+/// 统计每个类型被哪些结构体字段和服务方法引用，返回 类型名 -> 引用处链接 列表
+fn used_by(local_types: &[&str], structs: &[Struct], services: &[Service]) -> HashMap<String, Vec<String>> {
+    let mut result: HashMap<String, Vec<String>> =
+        local_types.iter().map(|name| (name.to_string(), Vec::new())).collect();
+    let mut add = |ty: &str, user: String| {
+        for name in referenced_types(ty) {
+            if let Some(users) = result.get_mut(&name)
+                && !users.contains(&user)
+            {
+                users.push(user.clone());
+            }
+        }
+    };
+    for s in structs {
+        for f in &s.fields {
+            add(&f.r#type, format!("[{}.{}](#type-{})", s.name, f.name, s.name));
+        }
+    }
+    for service in services {
+        for method in &service.methods {
+            let user = format!(
+                "[{}.{}](#method-{}-{})",
+                service.name, method.name, service.name, method.name
+            );
+            add(&method.request.name, user.clone());
+            add(&method.response.name, user);
+        }
+    }
+    result
+}
+
+/// 加载模板内容：配置了 template_dir 且其中存在同名文件时使用该文件，否则使用嵌入的模板
+pub fn load_template(name: &str) -> String {
+    if let Some(dir) = &config::get().template_dir {
+        let path = Path::new(dir).join(name);
+        if let Ok(content) = fs::read_to_string(&path) {
+            return content;
+        }
+    }
+    let template_data = Asset::get(name).unwrap_or_else(|| panic!("找不到 {} 模板", name));
+    String::from_utf8(template_data.data.into_owned()).expect("模板内容不是有效 utf8")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::idl_parser::{parse_handler, parse_idl};
+
+    const SAMPLE_THRIFT: &str = r#"
+        namespace rs volo.example
+
+        include "base.thrift"
+
+        enum Status {
+            ON = 1,
+            OFF = 2,
+        }
+
+        struct Item {
+            1: required i64 id,
+            2: optional list<Item> children,
+            3: required Status status,
+            4: optional base.Base base,
+        }
+
+        service ItemService {
+            Item GetItem (1: Item req),
+        }
+    "#;
+
+    #[test]
+    fn test_toc_and_type_links() {
+        let file = parse_idl(SAMPLE_THRIFT).unwrap();
+        let markdown = parse_handler(&file);
+
+        assert!(markdown.contains("- **volo.example**"));
+        assert!(markdown.contains("  - [Item](#type-Item)"));
+        assert!(markdown.contains("    - [GetItem](#method-ItemService-GetItem)"));
+        assert!(markdown.contains("### <a id=\"type-Status\"></a>Status"));
+        assert!(markdown.contains("| children | list\\<[Item](#type-Item)\\> | 否 | |"));
+        assert!(markdown.contains("| id | i64 | 是 | |"));
+        assert!(markdown.contains("[base.Base](base_api.md#type-Base)"));
+        assert!(markdown.contains("被引用：[Item.status](#type-Item)"));
+        assert!(markdown.contains("被引用：[Item.children](#type-Item)、[ItemService.GetItem](#method-ItemService-GetItem)"));
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::{BTreeSet, HashMap};
+use crate::config::GraphQLConfig;
+use crate::idl_parser::{find_annotation, parse_type, referenced_types, Document, Enum, Field, Method, Struct, TypeExpr};
+
+const INDENT: &str = "  ";
+
+/// 根据 Document 生成 GraphQL SDL
+///
+/// 结构体生成 type，作为方法参数（递归）用到的结构体另外生成 `{名称}Input` input 类型，
+/// 枚举生成 enum；服务方法按注解或配置划分到 Query 和 Mutation，请求结构体的字段展开为参数。
+/// GraphQL 的 Int 只有 32 位，i64 使用自定义标量 Long；map 没有对应类型，使用自定义标量 JSON。
+pub fn generate_graphql(doc: &Document, config: &GraphQLConfig) -> String {
+    let mut generator = Generator {
+        structs: doc.structs.iter().map(|s| (s.name.as_str(), s)).collect(),
+        enums: doc.enums.iter().map(|e| e.name.as_str()).collect(),
+        typedefs: doc.typedefs.iter().map(|t| (t.name.as_str(), t.r#type.as_str())).collect(),
+        scalars: BTreeSet::new(),
+    };
+
+    let mut queries = Vec::new();
+    let mut mutations = Vec::new();
+    let mut inputs = BTreeSet::new();
+    for service in &doc.services {
+        for method in &service.methods {
+            let args = if flatten_request(method) { &method.request.fields } else { &method.arguments };
+            for f in args {
+                generator.collect_inputs(&f.r#type, &mut inputs);
+            }
+            let field = generator.operation(method);
+            if is_query(&service.name, method, config) {
+                queries.push(field);
+            } else {
+                mutations.push(field);
+            }
+        }
+    }
+
+    let mut blocks = Vec::new();
+    for e in &doc.enums {
+        blocks.push(enum_block(e));
+    }
+    for s in &doc.structs {
+        blocks.push(generator.struct_block(s, false));
+    }
+    for name in &inputs {
+        let s = generator.structs[name.as_str()];
+        blocks.push(generator.struct_block(s, true));
+    }
+    for (name, fields) in [("Query", queries), ("Mutation", mutations)] {
+        if !fields.is_empty() {
+            blocks.push(format!("type {} {{\n{}\n}}", name, fields.join("\n")));
+        }
+    }
+
+    let mut scalars: Vec<String> = Vec::new();
+    if generator.scalars.contains("Long") {
+        scalars.push("\"64 位整数，JSON 中以数字表示\"\nscalar Long".to_string());
+    }
+    if generator.scalars.contains("JSON") {
+        scalars.push("\"任意 JSON 值，用于 Thrift map\"\nscalar JSON".to_string());
+    }
+    scalars.extend(blocks);
+    scalars.join("\n\n") + "\n"
+}
+
+/// 方法只有一个参数且是本文件中的结构体时，把结构体的字段展开为 GraphQL 参数
+fn flatten_request(method: &Method) -> bool {
+    method.arguments.len() <= 1 && !method.request.fields.is_empty()
+}
+
+/// 方法是否作为 Query，规则见 `GraphQLConfig`
+fn is_query(service: &str, method: &Method, config: &GraphQLConfig) -> bool {
+    if find_annotation(&method.annotations, "graphql.query").is_some() {
+        return true;
+    }
+    if find_annotation(&method.annotations, "graphql.mutation").is_some() {
+        return false;
+    }
+    let full_name = format!("{}.{}", service, method.name);
+    if config.queries.contains(&full_name) {
+        return true;
+    }
+    if config.mutations.contains(&full_name) {
+        return false;
+    }
+    config.query_prefixes.iter().any(|p| method.name.starts_with(p.as_str()))
+}
+
+struct Generator<'a> {
+    structs: HashMap<&'a str, &'a Struct>,
+    enums: Vec<&'a str>,
+    typedefs: HashMap<&'a str, &'a str>,
+    /// 用到的自定义标量
+    scalars: BTreeSet<&'static str>,
+}
+
+impl Generator<'_> {
+    /// 收集类型（递归）引用到的结构体，这些结构体需要生成 input 类型
+    fn collect_inputs(&self, ty: &str, inputs: &mut BTreeSet<String>) {
+        for name in referenced_types(ty) {
+            if let Some(target) = self.typedefs.get(name.as_str()) {
+                self.collect_inputs(target, inputs);
+            } else if let Some(s) = self.structs.get(name.as_str())
+                && inputs.insert(name)
+            {
+                for f in &s.fields {
+                    self.collect_inputs(&f.r#type, inputs);
+                }
+            }
+        }
+    }
+
+    /// Query / Mutation 中的字段，例如 `getItem(id: Long!): Item`
+    fn operation(&mut self, method: &Method) -> String {
+        let name = find_annotation(&method.annotations, "graphql.query")
+            .or_else(|| find_annotation(&method.annotations, "graphql.mutation"))
+            .filter(|n| !n.is_empty())
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| lower_camel(&method.name));
+
+        // 唯一的参数是本文件中的结构体时展开其字段，否则每个参数对应一个 GraphQL 参数，沿用 IDL 中的参数名
+        let args = if flatten_request(method) { &method.request.fields } else { &method.arguments };
+        let args: Vec<String> = args.iter().map(|f| format!("{}: {}", f.name, self.field_type(f, true))).collect();
+        let args = if args.is_empty() { String::new() } else { format!("({})", args.join(", ")) };
+        // GraphQL 字段必须有类型，void 方法返回 Boolean
+        let response = match method.response.name.as_str() {
+            "" | "void" => "Boolean".to_string(),
+            ty => self.gql_type(&parse_type(ty), false),
+        };
+
+        let mut lines = description(&method.comment, INDENT);
+        lines.push(format!("{}{}{}: {}", INDENT, name, args, response));
+        lines.join("\n")
+    }
+
+    fn struct_block(&mut self, s: &Struct, input: bool) -> String {
+        let union = s.kind == "union";
+        let comment = if union && !input {
+            format!("{}\n（union：只有一个字段有值）", s.comment).trim().to_string()
+        } else {
+            s.comment.clone()
+        };
+        let mut lines = description(&comment, "");
+        let name = type_name(&s.name, input);
+        lines.push(match (input, union) {
+            (true, true) => format!("input {} @oneOf {{", name),
+            (true, false) => format!("input {} {{", name),
+            _ => format!("type {} {{", name),
+        });
+        for f in &s.fields {
+            lines.extend(description(&f.comment, INDENT));
+            // union 的字段都可以为空
+            let ty = if union { self.gql_type(&parse_type(&f.r#type), input) } else { self.field_type(f, input) };
+            lines.push(format!("{}{}: {}", INDENT, f.name, ty));
+        }
+        // GraphQL 不允许没有字段的类型
+        if s.fields.is_empty() {
+            lines.push(format!("{}_empty: Boolean", INDENT));
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    /// 字段类型，required 字段不可为空
+    fn field_type(&mut self, f: &Field, input: bool) -> String {
+        let ty = self.gql_type(&parse_type(&f.r#type), input);
+        if f.attribute == "Required" { format!("{}!", ty) } else { ty }
+    }
+
+    fn gql_type(&mut self, expr: &TypeExpr, input: bool) -> String {
+        match expr {
+            TypeExpr::Base(base) => match base.as_str() {
+                "bool" => "Boolean".to_string(),
+                "byte" | "i8" | "i16" | "i32" => "Int".to_string(),
+                "i64" => {
+                    self.scalars.insert("Long");
+                    "Long".to_string()
+                }
+                "double" => "Float".to_string(),
+                "void" => "Boolean".to_string(),
+                _ => "String".to_string(),
+            },
+            TypeExpr::List(value) | TypeExpr::Set(value) => format!("[{}!]", self.gql_type(value, input)),
+            TypeExpr::Map(..) => {
+                self.scalars.insert("JSON");
+                "JSON".to_string()
+            }
+            TypeExpr::Named(name) => {
+                if let Some(target) = self.typedefs.get(name.as_str()) {
+                    return self.gql_type(&parse_type(target), input);
+                }
+                type_name(name, input && !self.enums.contains(&name.as_str()))
+            }
+        }
+    }
+}
+
+fn enum_block(e: &Enum) -> String {
+    let mut lines = description(&e.comment, "");
+    lines.push(format!("enum {} {{", type_name(&e.name, false)));
+    for v in &e.values {
+        lines.extend(description(&v.comment, INDENT));
+        lines.push(format!("{}{}", INDENT, v.name));
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+/// GraphQL 类型名不能包含 `.`，include 文件中的类型 `base.Base` 转换为 `base_Base`
+fn type_name(name: &str, input: bool) -> String {
+    let name = name.replace('.', "_");
+    if input { format!("{}Input", name) } else { name }
+}
+
+fn description(comment: &str, indent: &str) -> Vec<String> {
+    if comment.is_empty() {
+        return Vec::new();
+    }
+    if !comment.contains('\n') {
+        return vec![format!("{}\"{}\"", indent, comment.replace('"', "\\\""))];
+    }
+    let mut lines = vec![format!("{}\"\"\"", indent)];
+    lines.extend(comment.lines().map(|l| format!("{}{}", indent, l)));
+    lines.push(format!("{}\"\"\"", indent));
+    lines
+}
+
+/// `GetItem` 转换为 `getItem`
+fn lower_camel(name: &str) -> String {
+    let mut chars = name.chars();
+    chars.next().map(|c| c.to_lowercase().chain(chars).collect()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl_parser::{build_document, parse_idl};
+    use crate::source_map::apply_source_info;
+
+    const SAMPLE_THRIFT: &str = r#"
+        enum Status { ON = 1, OFF = 2 }
+
+        struct Filter { 1: optional Status status }
+
+        struct ListItemsRequest {
+            1: required i64 shop_id,
+            2: optional Filter filter,
+        }
+
+        /// 商品
+        struct Item {
+            1: required i64 id,
+            2: optional list<string> tags,
+            3: optional map<string, string> extra,
+        }
+
+        service ItemService {
+            /// 商品列表
+            list<Item> ListItems (1: ListItemsRequest req),
+            void Remove (1: Filter req),
+            Item Touch (1: Filter req) (graphql.query = 'touchedItem'),
+            Item GetItem (1: i64 id),
+            Item FindItem (1: string slug),
+            list<Item> SearchItems (1: Filter filter, 2: i32 page, 3: optional string keyword),
+        }
+    "#;
+
+    #[test]
+    fn test_generate_graphql() {
+        let mut doc = build_document(&parse_idl(SAMPLE_THRIFT).unwrap());
+        apply_source_info(&mut doc, SAMPLE_THRIFT);
+        let sdl = generate_graphql(&doc, &GraphQLConfig::default());
+
+        assert!(sdl.starts_with("\"64 位整数，JSON 中以数字表示\"\nscalar Long"));
+        assert!(sdl.contains("scalar JSON"));
+        assert!(sdl.contains("enum Status {\n  ON\n  OFF\n}"));
+        assert!(sdl.contains("\"商品\"\ntype Item {\n  id: Long!\n  tags: [String!]\n  extra: JSON\n}"));
+        assert!(sdl.contains("input FilterInput {\n  status: Status\n}"));
+        assert!(!sdl.contains("input ItemInput"));
+        assert!(sdl.contains(
+            "type Query {\n  \"商品列表\"\n  listItems(shop_id: Long!, filter: FilterInput): [Item!]\n  touchedItem(status: Status): Item\n  getItem(id: Long!): Item\n  findItem(slug: String!): Item\n  searchItems(filter: FilterInput!, page: Int!, keyword: String): [Item!]\n}"
+        ));
+        assert!(sdl.contains("type Mutation {\n  remove(status: Status): Boolean\n}"));
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tera::{Context, Tera};
+use crate::config::{self, Config};
+use crate::diagram_generator::generate_diagrams;
+use crate::doc_generator::{constraints_filter, load_template};
+use crate::idl_parser::{self, Document};
+use crate::sample_data_generator;
+
+/// 一个 IDL 文件对应的 HTML 页面数据
+#[derive(Debug, Serialize)]
+pub struct HtmlPage {
+    /// 页面名称，即 IDL 文件名（不含后缀），生成的文件为 `{name}.html`
+    pub name: String,
+    pub document: Document,
+    /// `sample_data_generator::generate_sample_values` 生成的示例数据
+    pub samples: Value,
+}
+
+/// 读取并解析给定的 IDL 文件，整理为 HTML 页面数据
+pub fn load_pages(idl_paths: &[String]) -> Result<Vec<HtmlPage>, String> {
+    let mut pages = Vec::new();
+    for idl_path in idl_paths {
+        let content = fs::read_to_string(idl_path)
+            .map_err(|e| format!("读取 IDL 文件失败: {}: {}", idl_path, e))?;
+        let file = idl_parser::parse_idl(&content).map_err(|e| format!("{}: {}", idl_path, e))?;
+        let name = Path::new(idl_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        pages.push(HtmlPage {
+            name,
+            document: idl_parser::build_document(&file),
+            samples: sample_data_generator::generate_sample_values(&file),
+        });
+    }
+    Ok(pages)
+}
+
+/// 根据所有页面生成完整的静态站点，返回 (文件名, HTML 内容) 列表
+///
+/// 每个页面都内联了样式、脚本和搜索索引，不依赖任何外部资源，可直接离线打开。
+/// 模板（例如 `template_dir` 中的自定义模板）有误时返回错误信息。
+pub fn generate_site(pages: &[HtmlPage]) -> Result<Vec<(String, String)>, String> {
+    render_site(pages, config::get())
+}
+
+fn render_site(pages: &[HtmlPage], config: &Config) -> Result<Vec<(String, String)>, String> {
+    let page_names: Vec<&str> = pages.iter().map(|p| p.name.as_str()).collect();
+    let search_index = build_search_index(pages);
+
+    let mut files = Vec::new();
+    for page in pages {
+        let html = render_page(Some(page), &page_names, &search_index, link_targets(page, pages), config)?;
+        files.push((format!("{}.html", page.name), html));
+    }
+    let index = render_page(None, &page_names, &search_index, HashMap::new(), config)?;
+    files.push(("index.html".to_string(), index));
+    Ok(files)
+}
+
+/// 使用 html_template.html 模板渲染单个页面，`page` 为 None 时渲染首页
+fn render_page(
+    page: Option<&HtmlPage>,
+    page_names: &[&str],
+    search_index: &str,
+    targets: HashMap<String, String>,
+    config: &Config,
+) -> Result<String, String> {
+    let template_str = load_template("html_template.html");
+
+    let mut tera = Tera::default();
+    tera.add_raw_template("html_template.html", &template_str).map_err(|e| template_error("解析", &e))?;
+    tera.register_filter(
+        "type_link",
+        move |value: &Value, _: &HashMap<String, Value>| -> tera::Result<Value> {
+            let ty = tera::try_get_value!("type_link", "value", String, value);
+            Ok(Value::String(link_type(&ty, &targets)))
+        },
+    );
+    tera.register_filter("constraints", constraints_filter);
+
+    let mut context = Context::new();
+    context.insert("page", &page);
+    context.insert("pages", page_names);
+    context.insert("search_index", search_index);
+    if let Some(page) = page {
+        context.insert("samples", &highlight_samples(&page.samples));
+    }
+    match page {
+        Some(page) if config.diagrams => {
+            let doc = &page.document;
+            context.insert("diagrams", &generate_diagrams(&doc.structs, &doc.enums, &doc.services));
+        }
+        _ => context.insert("diagrams", &false),
+    }
+    context.insert("mermaid_url", &config.mermaid_url);
+
+    tera.render("html_template.html", &context).map_err(|e| template_error("渲染", &e))
+}
+
+/// Tera 的错误信息只有一句概述，具体原因（语法错误的位置、缺少的变量等）在 source 链中
+fn template_error(action: &str, e: &tera::Error) -> String {
+    let mut message = format!("{} HTML 模板 html_template.html 失败: {}", action, e);
+    let mut source = std::error::Error::source(e);
+    while let Some(cause) = source {
+        message.push_str(&format!("\n  {}", cause));
+        source = cause.source();
+    }
+    message
+}
+
+/// 收集当前页面可以链接到的类型：本文件的类型使用页内锚点，
+/// 其它文件的类型按照 Thrift include 的写法 `文件名.类型名` 链接到对应页面
+fn link_targets(page: &HtmlPage, pages: &[HtmlPage]) -> HashMap<String, String> {
+    let mut targets = HashMap::new();
+    for other in pages {
+        let names = other
+            .document
+            .structs
+            .iter()
+            .map(|s| &s.name)
+            .chain(other.document.enums.iter().map(|e| &e.name));
+        for name in names {
+            if other.name == page.name {
+                targets.insert(name.clone(), format!("#type-{}", name));
+            } else {
+                targets.insert(
+                    format!("{}.{}", other.name, name),
+                    format!("{}.html#type-{}", other.name, name),
+                );
+            }
+        }
+    }
+    targets
+}
+
+/// 将类型字符串转义为 HTML，并把其中的自定义类型替换为指向定义处的链接
+/// 例如 `list<Item>` 转换为 `list&lt;<a href="#type-Item">Item</a>&gt;`
+fn link_type(ty: &str, targets: &HashMap<String, String>) -> String {
+    let mut html = String::new();
+    let mut token = String::new();
+    let flush = |token: &mut String, html: &mut String| {
+        if token.is_empty() {
+            return;
+        }
+        match targets.get(token.as_str()) {
+            Some(href) => html.push_str(&format!("<a href=\"{}\">{}</a>", href, escape_html(token))),
+            None => html.push_str(&escape_html(token)),
+        }
+        token.clear();
+    };
+    for c in ty.chars() {
+        if c.is_alphanumeric() || c == '_' || c == '.' {
+            token.push(c);
+        } else {
+            flush(&mut token, &mut html);
+            html.push_str(&escape_html(&c.to_string()));
+        }
+    }
+    flush(&mut token, &mut html);
+    html
+}
+
+/// 生成搜索索引（JSON 数组），包含所有页面中的服务、方法和类型
+fn build_search_index(pages: &[HtmlPage]) -> String {
+    let mut entries = Vec::new();
+    for page in pages {
+        let doc = &page.document;
+        for service in &doc.services {
+            entries.push(json!({
+                "name": service.name,
+                "kind": "service",
+                "page": page.name,
+                "href": format!("{}.html#service-{}", page.name, service.name),
+            }));
+            for method in &service.methods {
+                entries.push(json!({
+                    "name": format!("{}.{}", service.name, method.name),
+                    "kind": "method",
+                    "page": page.name,
+                    "href": format!("{}.html#method-{}-{}", page.name, service.name, method.name),
+                }));
+            }
+        }
+        for s in &doc.structs {
+            entries.push(json!({
+                "name": s.name,
+                "kind": s.kind,
+                "page": page.name,
+                "href": format!("{}.html#type-{}", page.name, s.name),
+            }));
+        }
+        for e in &doc.enums {
+            entries.push(json!({
+                "name": e.name,
+                "kind": "enum",
+                "page": page.name,
+                "href": format!("{}.html#type-{}", page.name, e.name),
+            }));
+        }
+    }
+    // 索引直接嵌入 <script> 标签中，需要避免出现 "</script>"
+    Value::Array(entries).to_string().replace("</", "<\\/")
+}
+
+/// 将 服务 -> 方法 -> { request, response } 结构中的示例数据转换为高亮后的 HTML
+fn highlight_samples(samples: &Value) -> Value {
+    let mut result = serde_json::Map::new();
+    if let Value::Object(services) = samples {
+        for (service, methods) in services {
+            let mut service_obj = serde_json::Map::new();
+            if let Value::Object(methods) = methods {
+                for (method, sample) in methods {
+                    let pretty = |v: &Value| {
+                        highlight_json(&serde_json::to_string_pretty(v).unwrap_or_default())
+                    };
+                    service_obj.insert(
+                        method.clone(),
+                        json!({
+                            "request": pretty(&sample["request"]),
+                            "response": pretty(&sample["response"]),
+                        }),
+                    );
+                }
+            }
+            result.insert(service.clone(), Value::Object(service_obj));
+        }
+    }
+    Value::Object(result)
+}
+
+/// 对格式化后的 JSON 文本做语法高亮，输出带 `json-*` class 的 HTML
+pub fn highlight_json(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut html = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' {
+            // 字符串：找到未被转义的结束引号
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i += 1;
+            let text: String = chars[start..i.min(chars.len())].iter().collect();
+            // 后面紧跟冒号的字符串是对象的 key
+            let is_key = chars[i.min(chars.len())..]
+                .iter()
+                .find(|c| !c.is_whitespace())
+                == Some(&':');
+            let class = if is_key { "json-key" } else { "json-string" };
+            html.push_str(&format!("<span class=\"{}\">{}</span>", class, escape_html(&text)));
+        } else if c == '-' || c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || "-+.eE".contains(chars[i])) {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            html.push_str(&format!("<span class=\"json-number\">{}</span>", text));
+        } else if c.is_ascii_alphabetic() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            html.push_str(&format!("<span class=\"json-literal\">{}</span>", text));
+        } else {
+            html.push_str(&escape_html(&c.to_string()));
+            i += 1;
+        }
+    }
+    html
+}
+
+/// 转义 HTML 文本和属性值中的特殊字符
+pub fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl_parser::{build_document, parse_idl};
+    use crate::sample_data_generator::generate_sample_values;
+
+    const SAMPLE_THRIFT: &str = r#"
+        namespace rs volo.example
+
+        enum Status {
+            ON = 1,
+            OFF,
+        }
+
+        struct Item {
+            1: required i64 id,
+            2: required string title,
+            3: optional list<Item> children,
+            4: required Status status,
+        }
+
+        service ItemService {
+            Item GetItem (1: Item req),
+        }
+    "#;
+
+    #[test]
+    fn test_generate_site() {
+        let file = parse_idl(SAMPLE_THRIFT).unwrap();
+        let page = HtmlPage {
+            name: "item".to_string(),
+            document: build_document(&file),
+            samples: generate_sample_values(&file),
+        };
+        let files = generate_site(&[page]).unwrap();
+        assert_eq!(files.len(), 2);
+        let (name, html) = &files[0];
+        assert_eq!(name, "item.html");
+        assert!(html.contains("id=\"service-ItemService\""));
+        assert!(html.contains("id=\"method-ItemService-GetItem\""));
+        assert!(html.contains("list&lt;<a href=\"#type-Item\">Item</a>&gt;"));
+        assert!(html.contains("<a href=\"#type-Status\">Status</a>"));
+        assert!(html.contains("<span class=\"json-key\">&quot;title&quot;</span>"));
+        assert!(!html.contains("<link"), "页面不应依赖外部资源");
+        assert_eq!(files[1].0, "index.html");
+    }
+
+    #[test]
+    fn test_offline_diagrams() {
+        let file = parse_idl(SAMPLE_THRIFT).unwrap();
+        let page = || HtmlPage {
+            name: "item".to_string(),
+            document: build_document(&file),
+            samples: generate_sample_values(&file),
+        };
+        let config = Config { diagrams: true, ..Config::default() };
+        let files = render_site(&[page()], &config).unwrap();
+        let html = &files[0].1;
+        assert!(html.contains("<pre class=\"mermaid\">classDiagram"));
+        assert!(!html.contains("src="), "页面不应加载外部脚本");
+        assert!(!html.contains("import mermaid"), "未配置 mermaid_url 时不应加载 mermaid");
+        assert!(!html.contains("\"http"), "页面不应引用外部地址");
+
+        let url = "https://cdn.example.com/mermaid.esm.min.mjs";
+        let config = Config { diagrams: true, mermaid_url: Some(url.to_string()), ..Config::default() };
+        let files = render_site(&[page()], &config).unwrap();
+        assert!(files[0].1.contains(&format!("import mermaid from \"{}\"", url)));
+    }
+
+    #[test]
+    fn test_template_error() {
+        let e = Tera::default().add_raw_template("html_template.html", "{% if %}").unwrap_err();
+        let message = template_error("解析", &e);
+        assert!(message.starts_with("解析 HTML 模板 html_template.html 失败"));
+        assert!(message.lines().count() > 1, "应包含具体的错误原因: {}", message);
+    }
+}
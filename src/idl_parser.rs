@@ -1,6 +1,6 @@
 use pilota_thrift_parser::parser::Parser;
-use pilota_thrift_parser::{File, Item};
-use serde::Serialize;
+use pilota_thrift_parser::{File, Item, StructLike, Ty, Type};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::File as StdFile;
 use std::io::Write;
@@ -9,39 +9,286 @@ use std::io::Write;
 
 use crate::doc_generator;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct Service {
     pub name: String,
     pub methods: Vec<Method>,
+    /// IDL 中的文档注释，由 `source_map::apply_source_info` 填充
+    pub comment: String,
+    /// 定义所在的行号（从 1 开始），未知时为 0
+    pub line: usize,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct Method {
     pub name: String,
+    /// 第一个参数的类型，为结构体时包含其字段
     pub request: Struct,
     pub response: Struct,
+    /// 全部参数，`request` 只对应其中第一个
+    pub arguments: Vec<Field>,
+    /// throws 声明的异常字段
+    pub throws: Vec<Field>,
+    pub annotations: Vec<Annotation>,
+    pub comment: String,
+    pub line: usize,
 }
 
-#[derive(Debug, Serialize,Clone)]
+#[derive(Debug, Serialize, Clone)]
 pub struct Struct {
     pub name: String,
+    /// 定义方式：struct、union 或 exception
+    pub kind: String,
     pub fields: Vec<Field>,
+    pub comment: String,
+    pub line: usize,
 }
 
 impl Default for Struct {
     fn default() -> Self {
         Struct {
             name: "".to_string(),
+            kind: "struct".to_string(),
             fields: vec![],
+            comment: String::new(),
+            line: 0,
         }
     }
 }
 
-#[derive(Debug, Serialize,Clone)]
+#[derive(Debug, Serialize, Clone)]
 pub struct Field {
+    pub id: i32,
     pub name: String,
     pub r#type: String,
     pub attribute: String,
+    pub annotations: Vec<Annotation>,
+    /// 默认值，例如 `3: optional i32 page_size = 20` 中的 `20`
+    pub default: Option<ConstValue>,
+    /// 由 `vt.*` 注解解析得到的校验约束
+    pub constraints: Constraints,
+    pub comment: String,
+    pub line: usize,
+}
+
+/// 字段的校验约束，由 thrift-gen-validator 风格的注解解析得到，
+/// 例如 `(vt.min_size = "1", vt.max_size = "20", vt.pattern = "^[a-z]+$")`
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Constraints {
+    /// 字符串长度或容器元素个数的下限和上限（vt.min_size、vt.max_size）
+    pub min_size: Option<usize>,
+    pub max_size: Option<usize>,
+    /// 数值范围（vt.gt、vt.ge、vt.lt、vt.le）
+    pub gt: Option<f64>,
+    pub ge: Option<f64>,
+    pub lt: Option<f64>,
+    pub le: Option<f64>,
+    /// 字符串需要匹配的正则表达式（vt.pattern）
+    pub pattern: Option<String>,
+    /// 允许的取值（vt.in），保留 IDL 中的写法，字符串带引号
+    pub r#in: Vec<String>,
+}
+
+impl Constraints {
+    /// 从字段注解中解析约束，无法解析的取值忽略；`vt.in` 可以重复出现，也可以写成 `"[1, 2, 3]"`
+    pub fn from_annotations(annotations: &[Annotation]) -> Constraints {
+        let mut c = Constraints::default();
+        for a in annotations {
+            // 注解值中的 `\\`、`\"` 是转义写法，例如 `vt.pattern = "^\\d+$"`
+            let value = a.value.trim().replace("\\\\", "\\").replace("\\\"", "\"");
+            let value = value.as_str();
+            match a.key.as_str() {
+                "vt.min_size" => c.min_size = value.parse().ok(),
+                "vt.max_size" => c.max_size = value.parse().ok(),
+                "vt.gt" => c.gt = parse_bound(value),
+                "vt.ge" => c.ge = parse_bound(value),
+                "vt.lt" => c.lt = parse_bound(value),
+                "vt.le" => c.le = parse_bound(value),
+                "vt.pattern" => c.pattern = Some(value.to_string()),
+                "vt.in" => match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                    Some(list) => c.r#in.extend(split_list(list).into_iter().filter(|v| !v.is_empty())),
+                    None => c.r#in.push(value.to_string()),
+                },
+                _ => {}
+            }
+        }
+        c
+    }
+
+    /// 文档中展示的约束说明，例如 `长度 1~20；取值 > 0 且 ≤ 100`，没有约束时为空字符串
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        match (self.min_size, self.max_size) {
+            (Some(min), Some(max)) => parts.push(format!("长度 {}~{}", min, max)),
+            (Some(min), None) => parts.push(format!("长度 ≥ {}", min)),
+            (None, Some(max)) => parts.push(format!("长度 ≤ {}", max)),
+            (None, None) => {}
+        }
+        let bounds: Vec<String> = [(">", self.gt), ("≥", self.ge), ("<", self.lt), ("≤", self.le)]
+            .iter()
+            .filter_map(|(op, bound)| bound.map(|b| format!("{} {}", op, b)))
+            .collect();
+        if !bounds.is_empty() {
+            parts.push(format!("取值 {}", bounds.join(" 且 ")));
+        }
+        if let Some(pattern) = &self.pattern {
+            parts.push(format!("匹配 {}", pattern));
+        }
+        if !self.r#in.is_empty() {
+            parts.push(format!("可选值 {}", self.r#in.join("、")));
+        }
+        parts.join("；")
+    }
+}
+
+/// 数值边界，NaN、inf 等无法比较的值忽略
+fn parse_bound(value: &str) -> Option<f64> {
+    value.parse().ok().filter(|b: &f64| b.is_finite())
+}
+
+/// 按逗号拆分 `vt.in` 的列表写法，引号中的逗号属于取值本身，例如 `"a,b", "c"` 拆分为 `"a,b"` 和 `"c"`
+fn split_list(list: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut quote = None;
+    for ch in list.chars() {
+        match (quote, ch) {
+            (None, ',') => items.push(std::mem::take(&mut item).trim().to_string()),
+            (None, '"' | '\'') => quote = Some(ch),
+            (Some(q), _) if q == ch => quote = None,
+            _ => {}
+        }
+        if ch != ',' || quote.is_some() {
+            item.push(ch);
+        }
+    }
+    items.push(item.trim().to_string());
+    items
+}
+
+/// 常量值，用于字段默认值和 const 定义
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", content = "value", rename_all = "lowercase")]
+pub enum ConstValue {
+    Bool(bool),
+    Int(i64),
+    Double(f64),
+    String(String),
+    /// 引用的常量或枚举项，例如 `DEFAULT_PAGE_SIZE`、`Status.ON`
+    Ref(String),
+    List(Vec<ConstValue>),
+    /// map 字面量，也用于结构体字面量（key 为字段名）
+    Map(Vec<(ConstValue, ConstValue)>),
+}
+
+/// const 定义，例如 `const i32 DEFAULT_PAGE_SIZE = 20`
+#[derive(Debug, Serialize, Clone)]
+pub struct Constant {
+    pub name: String,
+    pub r#type: String,
+    pub value: ConstValue,
+    pub comment: String,
+    pub line: usize,
+}
+
+/// IDL 中的注解，例如 `(api.get = '/item/:id')`
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Annotation {
+    pub key: String,
+    pub value: String,
+}
+
+/// 查找指定 key 的注解值
+pub fn find_annotation<'a>(annotations: &'a [Annotation], key: &str) -> Option<&'a str> {
+    annotations
+        .iter()
+        .find(|a| a.key == key)
+        .map(|a| a.value.as_str())
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Enum {
+    pub name: String,
+    pub values: Vec<EnumValue>,
+    pub comment: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct EnumValue {
+    pub name: String,
+    pub value: i64,
+    pub comment: String,
+    pub line: usize,
+}
+
+/// 类型别名，例如 `typedef i64 UserId`
+#[derive(Debug, Serialize, Clone)]
+pub struct Typedef {
+    pub name: String,
+    pub r#type: String,
+    pub comment: String,
+    pub line: usize,
+}
+
+/// 单个 IDL 文件整理后的完整信息，供 HTML 等需要全部类型的生成器使用
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct Document {
+    pub namespace: String,
+    pub structs: Vec<Struct>,
+    pub enums: Vec<Enum>,
+    pub typedefs: Vec<Typedef>,
+    pub constants: Vec<Constant>,
+    pub services: Vec<Service>,
+}
+
+/// 解析后的类型表达式，由 `Field.type` 这类字符串形式的类型解析得到
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeExpr {
+    /// Thrift 基础类型，例如 `i64`、`string`
+    Base(String),
+    List(Box<TypeExpr>),
+    Set(Box<TypeExpr>),
+    Map(Box<TypeExpr>, Box<TypeExpr>),
+    /// 自定义类型（结构体、枚举、typedef 等），可能带有 include 前缀，例如 `base.Base`
+    Named(String),
+}
+
+/// 将 `simplify_type` 生成的类型字符串解析为 `TypeExpr`
+pub fn parse_type(ty: &str) -> TypeExpr {
+    let ty = ty.trim();
+    let inner = |prefix: &str| {
+        ty.strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix('>'))
+    };
+    if let Some(value) = inner("list<") {
+        return TypeExpr::List(Box::new(parse_type(value)));
+    }
+    if let Some(value) = inner("set<") {
+        return TypeExpr::Set(Box::new(parse_type(value)));
+    }
+    if let Some(kv) = inner("map<") {
+        // 只在最外层的逗号处分割，避免拆开 `map<string, map<i32, string>>` 中嵌套的逗号
+        let mut depth = 0;
+        for (i, c) in kv.char_indices() {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                ',' if depth == 0 => {
+                    return TypeExpr::Map(
+                        Box::new(parse_type(&kv[..i])),
+                        Box::new(parse_type(&kv[i + 1..])),
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+    if BASE_TYPES.contains(&ty) {
+        TypeExpr::Base(ty.to_string())
+    } else {
+        TypeExpr::Named(ty.to_string())
+    }
 }
 
 /// 传入解析后的 File，整理出一份新的 API 文档（Markdown 格式字符串）
@@ -67,11 +314,15 @@ pub fn parse_handler(file: &File) -> String {
 }
 
 /// 解析 Thrift IDL 文件内容
-/// 该函数会解析 Thrift IDL 内容并将其结果保存到本地文件，同时返回解析后的 `File` 对象
+/// 该函数会解析 Thrift IDL 内容并返回解析后的 `File` 对象，
+/// 设置了 `VOLODOC_DEBUG` 环境变量时同时将解析结果保存到当前目录下的 output 文件
 pub fn parse_idl(idl_content: &str) -> Result<File, String> {
     match File::parse(idl_content) {
         Ok((_, file)) => {
-            // 将解析结果保存到文件，方便后续调试
+            // 将解析结果保存到文件，方便后续调试；check 等只读命令不应写入文件，因此默认关闭
+            if std::env::var_os("VOLODOC_DEBUG").is_none() {
+                return Ok(file);
+            }
             let debug_str = format!("{:#?}", file);
             let mut output = StdFile::create("output").map_err(|e| e.to_string())?;
             output
@@ -84,34 +335,158 @@ pub fn parse_idl(idl_content: &str) -> Result<File, String> {
     }
 }
 
-/// 从解析后的 `File` 对象中提取服务信息
-/// /// 收集所有结构体及其字段信息
+/// 从解析后的 `File` 对象中整理出完整的文档信息
+pub fn build_document(file: &File) -> Document {
+    Document {
+        namespace: namespace_of(file),
+        structs: collect_structs(file),
+        enums: collect_enums(file),
+        typedefs: collect_typedefs(file),
+        constants: collect_constants(file),
+        services: extract_services(file),
+    }
+}
+
+/// 获取文件的命名空间，优先使用 `namespace rs`，否则取第一个声明的命名空间
+pub fn namespace_of(file: &File) -> String {
+    let path = file.package.as_ref().or_else(|| {
+        file.items.iter().find_map(|item| match item {
+            Item::Namespace(ns) => Some(&ns.name),
+            _ => None,
+        })
+    });
+    path.map(|p| {
+        p.segments
+            .iter()
+            .map(|seg| seg.as_str())
+            .collect::<Vec<_>>()
+            .join(".")
+    })
+    .unwrap_or_default()
+}
+
+/// 收集文件中所有 include 的路径（相对于当前 IDL 文件所在目录）
+pub fn collect_includes(file: &File) -> Vec<String> {
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Include(inc) => Some(inc.path.0.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 遍历文件中所有的 struct、union 和 exception 定义，返回 (定义方式, 定义) 列表
+fn struct_likes(file: &File) -> Vec<(&'static str, &StructLike)> {
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Struct(st) => Some(("struct", &st.0)),
+            Item::Union(u) => Some(("union", &u.0)),
+            Item::Exception(e) => Some(("exception", &e.0)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 收集所有结构体（包括 union 和 exception）及其字段信息
 pub fn collect_structs(file: &File) -> Vec<Struct> {
+    struct_likes(file)
+        .into_iter()
+        .map(|(kind, st)| struct_to_struct(kind, st))
+        .collect()
+}
+
+/// 收集所有枚举定义，未显式赋值的枚举项按照 Thrift 规则在上一个值的基础上递增
+pub fn collect_enums(file: &File) -> Vec<Enum> {
     file.items
         .iter()
         .filter_map(|item| {
-            if let Item::Struct(st) = item {
-                Some(struct_to_struct(st))
-            } else {
-                None
-            }
+            let Item::Enum(e) = item else {
+                return None;
+            };
+            let mut next = 0;
+            let values = e
+                .values
+                .iter()
+                .map(|v| {
+                    let value = v.value.map(|c| c.0).unwrap_or(next);
+                    next = value + 1;
+                    EnumValue {
+                        name: v.name.0.to_string(),
+                        value,
+                        comment: String::new(),
+                        line: 0,
+                    }
+                })
+                .collect();
+            Some(Enum {
+                name: e.name.0.to_string(),
+                values,
+                comment: String::new(),
+                line: 0,
+            })
+        })
+        .collect()
+}
+
+/// 收集所有 typedef 定义
+pub fn collect_typedefs(file: &File) -> Vec<Typedef> {
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Typedef(t) => Some(Typedef {
+                name: t.alias.0.to_string(),
+                r#type: simplify_type(&t.r#type),
+                comment: String::new(),
+                line: 0,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 收集所有 const 定义
+pub fn collect_constants(file: &File) -> Vec<Constant> {
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Constant(c) => Some(Constant {
+                name: c.name.0.to_string(),
+                r#type: simplify_type(&c.r#type),
+                value: const_value(&c.value),
+                comment: String::new(),
+                line: 0,
+            }),
+            _ => None,
         })
         .collect()
 }
 
+/// 将 `pilota_thrift_parser::ConstValue` 转换为自定义的 `ConstValue`
+fn const_value(value: &pilota_thrift_parser::ConstValue) -> ConstValue {
+    use pilota_thrift_parser::ConstValue as Parsed;
+    match value {
+        Parsed::Bool(b) => ConstValue::Bool(*b),
+        Parsed::Int(i) => ConstValue::Int(i.0),
+        Parsed::Double(d) => ConstValue::Double(d.0.parse().unwrap_or_default()),
+        Parsed::String(s) => ConstValue::String(s.0.clone()),
+        Parsed::Path(path) => {
+            ConstValue::Ref(path.segments.iter().map(|seg| seg.as_str()).collect::<Vec<_>>().join("."))
+        }
+        Parsed::List(items) => ConstValue::List(items.iter().map(const_value).collect()),
+        Parsed::Map(entries) => {
+            ConstValue::Map(entries.iter().map(|(k, v)| (const_value(k), const_value(v))).collect())
+        }
+    }
+}
+
 /// 遍历所有的服务定义，并提取出每个服务的名称和方法定义
 pub fn extract_services(file: &File) -> Vec<Service> {
     // 先收集所有结构体，方便查找，注意这里 key 为结构体名称
-    let structs: HashMap<String, _> = file
-        .items
-        .iter()
-        .filter_map(|item| {
-            if let Item::Struct(st) = item {
-                Some((st.name.0.to_string(), st))
-            } else {
-                None
-            }
-        })
+    let structs: HashMap<String, _> = struct_likes(file)
+        .into_iter()
+        .map(|(kind, st)| (st.name.0.to_string(), (kind, st)))
         .collect();
 
     let mut services = Vec::new();
@@ -124,10 +499,10 @@ pub fn extract_services(file: &File) -> Vec<Service> {
                 let req_struct = if let Some(arg) = func.arguments.first() {
                     let type_str = simplify_type(&arg.ty);
                     structs.get(&type_str)
-                        .map(|st| struct_to_struct(st))
+                        .map(|(kind, st)| struct_to_struct(kind, st))
                         .unwrap_or_else(|| Struct {
                             name: type_str,
-                            fields: vec![],
+                            ..Default::default()
                         })
                 } else {
                     Struct::default()
@@ -136,124 +511,120 @@ pub fn extract_services(file: &File) -> Vec<Service> {
                 // 返回结构体：同样提取简化名称
                 let resp_type_str = simplify_type(&func.result_type);
                 let resp_struct = structs.get(&resp_type_str)
-                    .map(|st| struct_to_struct(st))
+                    .map(|(kind, st)| struct_to_struct(kind, st))
                     .unwrap_or_else(|| Struct {
                         name: resp_type_str,
-                        fields: vec![],
+                        ..Default::default()
                     });
 
                 methods.push(Method {
                     name: func.name.0.to_string(),
                     request: req_struct,
                     response: resp_struct,
+                    arguments: func.arguments.iter().map(field_to_field).collect(),
+                    throws: func.throws.iter().map(field_to_field).collect(),
+                    annotations: convert_annotations(&func.annotations),
+                    comment: String::new(),
+                    line: 0,
                 });
             }
      
             services.push(Service {
                 name: s.name.0.to_string(),
                 methods,
+                comment: String::new(),
+                line: 0,
             });
         }
     }
     services
 }
 
-/// 将 `pilota_thrift_parser::StructLike` 转换为自定义的 `Struct` 类型
-/// 该函数将 Thrift 结构体的字段和类型转换为简化后的数据结构
-fn simplify_type(ty: &impl std::fmt::Debug) -> String {
-    let debug_str = format!("{:?}", ty);
-
-    // 处理 Map 类型：例如
-    // "Map { key: Type(String, Annotations([])), value: Type(String, Annotations([])) }"
-    if debug_str.contains("Map") && debug_str.contains("key:") && debug_str.contains("value:") {
-        let key_type = {
-            if let Some(key_pos) = debug_str.find("key:") {
-                if let Some(type_pos) = debug_str[key_pos..].find("Type(") {
-                    let key_type_start = key_pos + type_pos + "Type(".len();
-                    if let Some(comma_pos) = debug_str[key_type_start..].find(',') {
-                        debug_str[key_type_start..key_type_start+comma_pos]
-                            .trim()
-                            .to_lowercase()
-                    } else {
-                        "".to_string()
-                    }
-                } else { "".to_string() }
-            } else { "".to_string() }
-        };
-
-        let value_type = {
-            if let Some(val_pos) = debug_str.find("value:") {
-                if let Some(val_type_pos) = debug_str[val_pos..].find("Type(") {
-                    let value_type_start = val_pos + val_type_pos + "Type(".len();
-                    if let Some(val_comma_pos) = debug_str[value_type_start..].find(',') {
-                        debug_str[value_type_start..value_type_start+val_comma_pos]
-                            .trim()
-                            .to_lowercase()
-                    } else {
-                        "".to_string()
-                    }
-                } else { "".to_string() }
-            } else { "".to_string() }
-        };
-
-        if !key_type.is_empty() && !value_type.is_empty() {
-            return format!("map<{}, {}>", key_type, value_type);
+/// 将 Thrift 类型转换为 IDL 写法的字符串表示，例如 `i64`、`list<Item>`、`map<string, base.Base>`
+fn simplify_type(ty: &Type) -> String {
+    match &ty.0 {
+        Ty::String => "string".to_string(),
+        Ty::Void => "void".to_string(),
+        Ty::Byte => "byte".to_string(),
+        Ty::Bool => "bool".to_string(),
+        Ty::Binary => "binary".to_string(),
+        Ty::I8 => "i8".to_string(),
+        Ty::I16 => "i16".to_string(),
+        Ty::I32 => "i32".to_string(),
+        Ty::I64 => "i64".to_string(),
+        Ty::Double => "double".to_string(),
+        Ty::Uuid => "uuid".to_string(),
+        Ty::List { value, .. } => format!("list<{}>", simplify_type(value)),
+        Ty::Set { value, .. } => format!("set<{}>", simplify_type(value)),
+        Ty::Map { key, value, .. } => {
+            format!("map<{}, {}>", simplify_type(key), simplify_type(value))
         }
+        // 引用其它文件中的类型时保留前缀，例如 "base.Base"
+        Ty::Path(path) => path
+            .segments
+            .iter()
+            .map(|seg| seg.as_str())
+            .collect::<Vec<_>>()
+            .join("."),
     }
+}
 
-    // 处理 List 类型，例如 "List(Type(String, Annotations([])))"
-    if debug_str.contains("List(") {
-       if let Some(list_pos) = debug_str.find("List(") {
-           let list_content = &debug_str[list_pos + "List(".len()..];
-           if let Some(end) = list_content.find(')') {
-              // 递归调用 simplify_type 处理内部类型
-              let inner = &list_content[..end];
-              let inner_type = simplify_type(&inner);
-              return format!("list<{}>", inner_type.to_lowercase());
-           }
-       }
-    }
-
-    // 处理 Path 格式类型：例如
-    // "Path(Path { segments: [Ident("Item")] })" => 返回 "Item"
-    if debug_str.contains("Path") {
-        if let Some(ident_pos) = debug_str.find("Ident(") {
-            let start = ident_pos + "Ident(".len();
-            if let Some(end) = debug_str[start..].find(')') {
-                let type_name = &debug_str[start..start+end];
-                return type_name.trim_matches('"').to_string();
-            }
+/// Thrift 内置的基础类型名称
+pub const BASE_TYPES: [&str; 11] = [
+    "string", "void", "byte", "bool", "binary", "i8", "i16", "i32", "i64", "double", "uuid",
+];
+
+/// 提取类型字符串中引用到的自定义类型名称
+/// 例如 `map<string, list<Item>>` 返回 `["Item"]`
+pub fn referenced_types(ty: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for token in ty.split(|c: char| c == '<' || c == '>' || c == ',' || c.is_whitespace()) {
+        if token.is_empty() || matches!(token, "list" | "set" | "map") || BASE_TYPES.contains(&token) {
+            continue;
         }
-    }
-    
-    // 处理 Type(...) 格式，例如 "Type(I64, Annotations([]))" => 返回 "I64"
-    if debug_str.starts_with("Type(") {
-        if let Some(comma) = debug_str.find(',') {
-            // 跳过前面的 "Type(" (5字符)
-            let inner = &debug_str[5..comma];
-            return inner.trim().to_string();
+        if !names.iter().any(|n| n == token) {
+            names.push(token.to_string());
         }
     }
-    
-    // 默认直接返回 debug 字符串
-    debug_str
+    names
 }
 
-fn struct_to_struct(st: &pilota_thrift_parser::StructLike) -> Struct {
+/// 将 `pilota_thrift_parser::StructLike` 转换为自定义的 `Struct` 类型
+fn struct_to_struct(kind: &str, st: &StructLike) -> Struct {
     Struct {
         name: st.name.0.to_string(),
-        fields: st
-            .fields
-            .iter()
-            .map(|f| Field {
-                name: f.name.0.to_string(),
-                r#type: simplify_type(&f.ty),
-                attribute: format!("{:?}", f.attribute),
-            })
-            .collect(),
+        kind: kind.to_string(),
+        fields: st.fields.iter().map(field_to_field).collect(),
+        comment: String::new(),
+        line: 0,
     }
 }
 
+fn field_to_field(f: &pilota_thrift_parser::Field) -> Field {
+    let annotations = convert_annotations(&f.annotations);
+    Field {
+        id: f.id,
+        name: f.name.0.to_string(),
+        r#type: simplify_type(&f.ty),
+        attribute: format!("{:?}", f.attribute),
+        constraints: Constraints::from_annotations(&annotations),
+        annotations,
+        default: f.default.as_ref().map(const_value),
+        comment: String::new(),
+        line: 0,
+    }
+}
+
+fn convert_annotations(annotations: &pilota_thrift_parser::Annotations) -> Vec<Annotation> {
+    annotations
+        .iter()
+        .map(|a| Annotation {
+            key: a.key.clone(),
+            value: a.value.0.clone(),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,110 @@
+use std::path::Path;
+use serde::Serialize;
+use crate::idl_parser::Document;
+use crate::resolver;
+
+/// IR 格式版本，字段含义变化或删除字段时递增，只新增字段时不变
+pub const IR_VERSION: u32 = 1;
+
+/// 导出给其它工具使用的中间表示（IR）
+///
+/// 结构如下（`volodoc ir --format json` 的输出）：
+/// - `version`：IR 格式版本，见 [`IR_VERSION`]
+/// - `generator`：生成该 IR 的 volodoc 版本
+/// - `files`：每个 IDL 文件一项，只包含文件本身的定义，被 include 的文件作为单独的项列出
+///
+/// 每个文件包含 `path`、`includes`、`namespace`、`structs`、`enums`、`typedefs`、`constants` 和 `services`。
+/// 常量带有 `name`、`type` 和 `value`（`{"kind": "int", "value": 1}` 形式，`kind` 为 bool、int、double、
+/// string、ref、list 或 map），字段的 `default` 也使用这种形式。所有定义都带有 `comment`（文档注释，没有时为空字符串）和 `line`（从 1 开始的行号），
+/// 字段还带有 `id`、`type`（IDL 写法，例如 `list<base.Base>`）、`attribute`
+/// （`Required`、`Optional` 或 `Default`）和 `annotations`。
+#[derive(Debug, Serialize)]
+pub struct Ir {
+    pub version: u32,
+    pub generator: String,
+    pub files: Vec<IrFile>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IrFile {
+    pub path: String,
+    /// include 语句中的路径（相对于当前文件所在目录）
+    pub includes: Vec<String>,
+    #[serde(flatten)]
+    pub document: Document,
+}
+
+/// 读取 IDL 文件生成 IR，被 include 的文件会一并加入（每个文件只出现一次）
+pub fn build_ir(idl_paths: &[String]) -> Result<Ir, String> {
+    let mut files: Vec<IrFile> = Vec::new();
+    let mut pending: Vec<String> = idl_paths.iter().rev().cloned().collect();
+    while let Some(path) = pending.pop() {
+        if files.iter().any(|f| same_file(&f.path, &path)) {
+            continue;
+        }
+        let (document, includes) = resolver::load_document(Path::new(&path))?;
+        let dir = Path::new(&path).parent().unwrap_or(Path::new(""));
+        for include in includes.iter().rev() {
+            pending.push(dir.join(include).to_string_lossy().to_string());
+        }
+        files.push(IrFile { path, includes, document });
+    }
+    Ok(Ir {
+        version: IR_VERSION,
+        generator: format!("volodoc {}", env!("CARGO_PKG_VERSION")),
+        files,
+    })
+}
+
+/// 按输出格式（json 或 yaml）序列化 IR
+pub fn render_ir(ir: &Ir, format: &str) -> Result<String, String> {
+    match format {
+        "json" => serde_json::to_string_pretty(ir).map_err(|e| e.to_string()),
+        "yaml" => serde_yaml::to_string(ir).map_err(|e| e.to_string()),
+        _ => Err(format!("不支持的 IR 格式：{}（可选 json、yaml）", format)),
+    }
+}
+
+fn same_file(a: &str, b: &str) -> bool {
+    match (Path::new(a).canonicalize(), Path::new(b).canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs;
+
+    #[test]
+    fn test_build_ir() {
+        let dir = temp_dir().join("volodoc_ir_test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("base.thrift"), "/// 公共字段\nstruct Base { 1: string log_id }").unwrap();
+        fs::write(
+            dir.join("item.thrift"),
+            "include \"base.thrift\"\n\nstruct Item {\n    1: required i64 id (api.path = 'id'), // 商品 ID\n    2: base.Base base,\n}",
+        )
+        .unwrap();
+
+        let item = dir.join("item.thrift").to_string_lossy().to_string();
+        let base = dir.join("base.thrift").to_string_lossy().to_string();
+        let ir = build_ir(&[item, base]).unwrap();
+        assert_eq!(ir.files.len(), 2);
+        let value = serde_json::to_value(&ir).unwrap();
+        assert_eq!(value["version"], IR_VERSION);
+        let field = &value["files"][0]["structs"][0]["fields"][0];
+        assert_eq!(field["line"], 4);
+        assert_eq!(field["comment"], "商品 ID");
+        assert_eq!(field["annotations"][0]["key"], "api.path");
+        assert_eq!(value["files"][1]["structs"][0]["comment"], "公共字段");
+
+        let yaml = render_ir(&ir, "yaml").unwrap();
+        assert!(yaml.contains("version: 1"));
+        assert!(render_ir(&ir, "xml").is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,500 @@
+use std::collections::HashMap;
+use std::path::Path;
+use serde::Serialize;
+use crate::config;
+use crate::idl_parser::{Document, Enum, Field, Method, Service, Struct};
+use crate::resolver;
+
+/// 变化类型
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// 两个版本 IDL 之间的一处变化
+#[derive(Debug, Serialize, Clone)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// 是否为不兼容变化
+    pub breaking: bool,
+    /// 变化的对象：service、method、argument、struct、field、enum、enum_value 或 typedef
+    pub target: &'static str,
+    /// 所属的定义：字段所在的结构体、方法所在的服务、参数所在的 `服务.方法`、枚举项所在的枚举；顶层定义为其自身名称
+    pub owner: String,
+    pub name: String,
+    /// 变化前后的类型、编号等，新增时没有 before，删除时没有 after
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub message: String,
+}
+
+impl Change {
+    /// 便于阅读的位置，例如 `Item.id`
+    pub fn path(&self) -> String {
+        if self.owner == self.name { self.name.clone() } else { format!("{}.{}", self.owner, self.name) }
+    }
+}
+
+/// 一个 IDL 文件两个版本之间的比较结果
+pub struct FileDiff {
+    /// 比较单个文件时为 `旧路径 -> 新路径`，比较目录时为文件名
+    pub name: String,
+    pub old: Document,
+    pub new: Document,
+    pub changes: Vec<Change>,
+}
+
+/// 比较两个 IDL 文件，或两个目录中的同名 IDL 文件（只存在于一侧的文件视为整体新增或删除）
+pub fn diff_paths(old: &str, new: &str) -> Result<Vec<FileDiff>, String> {
+    let load = |path: Option<&str>| match path {
+        Some(path) => resolver::load_resolved_document(path),
+        None => Ok(Document::default()),
+    };
+    if !Path::new(old).is_dir() {
+        let (old_doc, new_doc) = (load(Some(old))?, load(Some(new))?);
+        let changes = diff_documents(&old_doc, &new_doc);
+        return Ok(vec![FileDiff { name: format!("{} -> {}", old, new), old: old_doc, new: new_doc, changes }]);
+    }
+
+    let stem = |path: &String| Path::new(path).file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let old_files = config::thrift_files(old)?;
+    let new_files = config::thrift_files(new)?;
+    let mut names: Vec<String> = old_files.iter().chain(&new_files).map(stem).collect();
+    names.sort();
+    names.dedup();
+    let mut result = Vec::new();
+    for name in names {
+        let find = |files: &[String]| files.iter().find(|p| stem(p) == name).cloned();
+        let (old_doc, new_doc) = (load(find(&old_files).as_deref())?, load(find(&new_files).as_deref())?);
+        let changes = diff_documents(&old_doc, &new_doc);
+        result.push(FileDiff { name: format!("{}.thrift", name), old: old_doc, new: new_doc, changes });
+    }
+    Ok(result)
+}
+
+/// 比较两个版本的 Document（通常由 `resolver::load_resolved_document` 加载），返回全部变化
+///
+/// 字段和方法参数按编号匹配：编号相同但名称和类型都不同视为字段编号被复用；同名字段编号变化、
+/// 类型变化、optional 变为 required、新增 required 字段，以及删除字段、方法、枚举项、类型都属于不兼容变化。
+/// 被删除的服务如果与新增的服务方法完全相同，视为服务改名。
+pub fn diff_documents(old: &Document, new: &Document) -> Vec<Change> {
+    let mut differ = Differ {
+        changes: Vec::new(),
+        old_typedefs: old.typedefs.iter().map(|t| (t.name.clone(), t.r#type.clone())).collect(),
+        new_typedefs: new.typedefs.iter().map(|t| (t.name.clone(), t.r#type.clone())).collect(),
+    };
+    differ.diff_services(&old.services, &new.services);
+    differ.diff_structs(&old.structs, &new.structs);
+    differ.diff_enums(&old.enums, &new.enums);
+    differ.diff_typedefs(old, new);
+    differ.changes
+}
+
+struct Differ {
+    changes: Vec<Change>,
+    old_typedefs: HashMap<String, String>,
+    new_typedefs: HashMap<String, String>,
+}
+
+impl Differ {
+    /// `at` 为 (所属定义, 名称)，`values` 为 (变化前, 变化后)
+    fn push(
+        &mut self,
+        kind: ChangeKind,
+        breaking: bool,
+        target: &'static str,
+        at: (&str, &str),
+        values: (Option<String>, Option<String>),
+        message: String,
+    ) {
+        self.changes.push(Change {
+            kind,
+            breaking,
+            target,
+            owner: at.0.to_string(),
+            name: at.1.to_string(),
+            before: values.0,
+            after: values.1,
+            message,
+        });
+    }
+
+    fn diff_services(&mut self, old: &[Service], new: &[Service]) {
+        let removed: Vec<&Service> = old.iter().filter(|s| !new.iter().any(|n| n.name == s.name)).collect();
+        let mut added: Vec<&Service> = new.iter().filter(|s| !old.iter().any(|o| o.name == s.name)).collect();
+
+        for service in removed {
+            // 方法完全相同的新服务视为改名
+            let renamed = added.iter().position(|a| method_names(a) == method_names(service));
+            match renamed {
+                Some(index) => {
+                    let to = added.remove(index);
+                    self.push(
+                        ChangeKind::Modified, true, "service", (&service.name, &service.name), (Some(service.name.clone()), Some(to.name.clone())),
+                        format!("服务 {} 改名为 {}", service.name, to.name),
+                    );
+                    self.diff_methods(service, to);
+                }
+                None => self.push(
+                    ChangeKind::Removed, true, "service", (&service.name, &service.name), (None, None),
+                    format!("删除服务 {}", service.name),
+                ),
+            }
+        }
+        for service in added {
+            self.push(
+                ChangeKind::Added, false, "service", (&service.name, &service.name), (None, None),
+                format!("新增服务 {}", service.name),
+            );
+        }
+        for service in old {
+            if let Some(to) = new.iter().find(|n| n.name == service.name) {
+                self.diff_methods(service, to);
+            }
+        }
+    }
+
+    fn diff_methods(&mut self, old: &Service, new: &Service) {
+        let owner = &new.name;
+        for method in &old.methods {
+            let Some(to) = new.methods.iter().find(|m| m.name == method.name) else {
+                self.push(
+                    ChangeKind::Removed, true, "method", (owner, &method.name), (Some(signature(method)), None),
+                    format!("删除方法 {}.{}", owner, method.name),
+                );
+                continue;
+            };
+            // 参数与结构体字段一样按编号比较
+            self.diff_fields(&format!("{}.{}", owner, method.name), &method.arguments, &to.arguments, "argument");
+            if !self.same_type(&method.response.name, &to.response.name) {
+                self.push(
+                    ChangeKind::Modified, true, "method", (owner, &method.name), (Some(signature(method)), Some(signature(to))),
+                    format!("方法 {}.{} 的返回类型由 {} 变为 {}", owner, method.name, method.response.name, to.response.name),
+                );
+            } else if throws(method) != throws(to) {
+                self.push(
+                    ChangeKind::Modified, false, "method", (owner, &method.name), (Some(signature(method)), Some(signature(to))),
+                    format!("方法 {}.{} 声明的异常发生变化", owner, method.name),
+                );
+            }
+        }
+        for method in &new.methods {
+            if !old.methods.iter().any(|m| m.name == method.name) {
+                self.push(
+                    ChangeKind::Added, false, "method", (owner, &method.name), (None, Some(signature(method))),
+                    format!("新增方法 {}.{}", owner, method.name),
+                );
+            }
+        }
+    }
+
+    fn diff_structs(&mut self, old: &[Struct], new: &[Struct]) {
+        for s in old {
+            let Some(to) = new.iter().find(|n| n.name == s.name) else {
+                self.push(
+                    ChangeKind::Removed, true, "struct", (&s.name, &s.name), (Some(s.kind.clone()), None),
+                    format!("删除 {} {}", s.kind, s.name),
+                );
+                continue;
+            };
+            if s.kind != to.kind {
+                self.push(
+                    ChangeKind::Modified, true, "struct", (&s.name, &s.name), (Some(s.kind.clone()), Some(to.kind.clone())),
+                    format!("{} 由 {} 变为 {}", s.name, s.kind, to.kind),
+                );
+            }
+            self.diff_fields(&to.name, &s.fields, &to.fields, "field");
+        }
+        for s in new {
+            if !old.iter().any(|o| o.name == s.name) {
+                self.push(
+                    ChangeKind::Added, false, "struct", (&s.name, &s.name), (None, Some(s.kind.clone())),
+                    format!("新增 {} {}", s.kind, s.name),
+                );
+            }
+        }
+    }
+
+    /// 比较结构体字段（target 为 field）或方法参数（target 为 argument），owner 为结构体名或 `服务.方法`
+    fn diff_fields(&mut self, owner: &str, old: &[Field], new: &[Field], target: &'static str) {
+        let noun = if target == "argument" { "参数" } else { "字段" };
+        for f in old {
+            let by_id = new.iter().find(|n| n.id == f.id);
+            let by_name = new.iter().find(|n| n.name == f.name);
+            match (by_id, by_name) {
+                (Some(to), _) if to.name == f.name => self.diff_field(owner, f, to, target),
+                (Some(to), _) if self.same_type(&f.r#type, &to.r#type) => self.push(
+                    ChangeKind::Modified, false, target, (owner, &to.name), (Some(describe_field(f)), Some(describe_field(to))),
+                    format!("{}.{} 改名为 {}（编号 {} 不变）", owner, f.name, to.name, f.id),
+                ),
+                (Some(to), _) => self.push(
+                    ChangeKind::Modified, true, target, (owner, &to.name), (Some(describe_field(f)), Some(describe_field(to))),
+                    format!("{}编号 {} 被复用：{} {} 变为 {} {}", noun, f.id, f.r#type, f.name, to.r#type, to.name),
+                ),
+                (None, Some(to)) => self.push(
+                    ChangeKind::Modified, true, target, (owner, &f.name), (Some(describe_field(f)), Some(describe_field(to))),
+                    format!("{}.{} 的编号由 {} 变为 {}", owner, f.name, f.id, to.id),
+                ),
+                (None, None) => self.push(
+                    ChangeKind::Removed, true, target, (owner, &f.name), (Some(describe_field(f)), None),
+                    format!("删除{} {}.{}", noun, owner, f.name),
+                ),
+            }
+        }
+        for f in new {
+            if old.iter().any(|o| o.id == f.id || o.name == f.name) {
+                continue;
+            }
+            let required = f.attribute == "Required";
+            let message = if required {
+                format!("新增 required {} {}.{}，旧版本的调用方不会传递该{}", noun, owner, f.name, noun)
+            } else {
+                format!("新增{} {}.{}", noun, owner, f.name)
+            };
+            self.push(
+                ChangeKind::Added, required, target, (owner, &f.name), (None, Some(describe_field(f))),
+                message,
+            );
+        }
+    }
+
+    fn diff_field(&mut self, owner: &str, old: &Field, new: &Field, target: &'static str) {
+        let (before, after) = (Some(describe_field(old)), Some(describe_field(new)));
+        if !self.same_type(&old.r#type, &new.r#type) {
+            self.push(
+                ChangeKind::Modified, true, target, (owner, &new.name), (before, after),
+                format!("{}.{} 的类型由 {} 变为 {}", owner, new.name, old.r#type, new.r#type),
+            );
+            return;
+        }
+        if old.attribute != new.attribute {
+            let to_required = new.attribute == "Required";
+            let from_required = old.attribute == "Required";
+            self.push(
+                ChangeKind::Modified, to_required || from_required, target, (owner, &new.name), (before, after),
+                format!(
+                    "{}.{} 由 {} 变为 {}",
+                    owner, new.name, old.attribute.to_lowercase(), new.attribute.to_lowercase()
+                ),
+            );
+        } else if old.r#type != new.r#type {
+            // 展开 typedef 后类型相同
+            self.push(
+                ChangeKind::Modified, false, target, (owner, &new.name), (before, after),
+                format!("{}.{} 的类型写法由 {} 变为 {}（实际类型相同）", owner, new.name, old.r#type, new.r#type),
+            );
+        }
+    }
+
+    fn diff_enums(&mut self, old: &[Enum], new: &[Enum]) {
+        for e in old {
+            let Some(to) = new.iter().find(|n| n.name == e.name) else {
+                self.push(
+                    ChangeKind::Removed, true, "enum", (&e.name, &e.name), (None, None),
+                    format!("删除枚举 {}", e.name),
+                );
+                continue;
+            };
+            for v in &e.values {
+                match to.values.iter().find(|n| n.name == v.name) {
+                    None => self.push(
+                        ChangeKind::Removed, true, "enum_value", (&e.name, &v.name), (Some(v.value.to_string()), None),
+                        format!("删除枚举项 {}.{}", e.name, v.name),
+                    ),
+                    Some(n) if n.value != v.value => self.push(
+                        ChangeKind::Modified, true, "enum_value", (&e.name, &v.name), (Some(v.value.to_string()), Some(n.value.to_string())),
+                        format!("枚举项 {}.{} 的值由 {} 变为 {}", e.name, v.name, v.value, n.value),
+                    ),
+                    _ => {}
+                }
+            }
+            for v in &to.values {
+                if !e.values.iter().any(|o| o.name == v.name) {
+                    self.push(
+                        ChangeKind::Added, false, "enum_value", (&e.name, &v.name), (None, Some(v.value.to_string())),
+                        format!("新增枚举项 {}.{}", e.name, v.name),
+                    );
+                }
+            }
+        }
+        for e in new {
+            if !old.iter().any(|o| o.name == e.name) {
+                self.push(
+                    ChangeKind::Added, false, "enum", (&e.name, &e.name), (None, None),
+                    format!("新增枚举 {}", e.name),
+                );
+            }
+        }
+    }
+
+    fn diff_typedefs(&mut self, old: &Document, new: &Document) {
+        for t in &old.typedefs {
+            match new.typedefs.iter().find(|n| n.name == t.name) {
+                None => self.push(
+                    ChangeKind::Removed, true, "typedef", (&t.name, &t.name), (Some(t.r#type.clone()), None),
+                    format!("删除 typedef {}", t.name),
+                ),
+                Some(n) if !self.same_type(&t.r#type, &n.r#type) => self.push(
+                    ChangeKind::Modified, true, "typedef", (&t.name, &t.name), (Some(t.r#type.clone()), Some(n.r#type.clone())),
+                    format!("typedef {} 由 {} 变为 {}", t.name, t.r#type, n.r#type),
+                ),
+                _ => {}
+            }
+        }
+        for t in &new.typedefs {
+            if !old.typedefs.iter().any(|o| o.name == t.name) {
+                self.push(
+                    ChangeKind::Added, false, "typedef", (&t.name, &t.name), (None, Some(t.r#type.clone())),
+                    format!("新增 typedef {}", t.name),
+                );
+            }
+        }
+    }
+
+    /// 展开 typedef 后比较两个类型是否相同
+    fn same_type(&self, old: &str, new: &str) -> bool {
+        old == new || expand_typedefs(old, &self.old_typedefs) == expand_typedefs(new, &self.new_typedefs)
+    }
+}
+
+/// 将类型字符串中的 typedef 名称替换为实际类型
+fn expand_typedefs(ty: &str, typedefs: &HashMap<String, String>) -> String {
+    let mut result = String::new();
+    let mut token = String::new();
+    for c in ty.chars().chain(std::iter::once('\0')) {
+        if c.is_alphanumeric() || c == '_' || c == '.' {
+            token.push(c);
+            continue;
+        }
+        match typedefs.get(&token) {
+            Some(target) if target != &token => result.push_str(&expand_typedefs(target, typedefs)),
+            _ => result.push_str(&token),
+        }
+        token.clear();
+        if c != '\0' {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn method_names(service: &Service) -> Vec<&str> {
+    let mut names: Vec<&str> = service.methods.iter().map(|m| m.name.as_str()).collect();
+    names.sort();
+    names
+}
+
+fn throws(method: &Method) -> Vec<&str> {
+    method.throws.iter().map(|e| e.r#type.as_str()).collect()
+}
+
+/// 方法签名，例如 `Item GetItem(GetItemRequest)`，多个参数时为 `Item GetItem(i64, i32)`
+fn signature(method: &Method) -> String {
+    let arguments: Vec<&str> = method.arguments.iter().map(|a| a.r#type.as_str()).collect();
+    format!("{} {}({})", method.response.name, method.name, arguments.join(", "))
+}
+
+/// 字段描述，例如 `1: required i64 id`
+fn describe_field(f: &Field) -> String {
+    format!("{}: {} {} {}", f.id, f.attribute.to_lowercase(), f.r#type, f.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl_parser::{build_document, parse_idl};
+
+    fn document(content: &str) -> Document {
+        build_document(&parse_idl(content).unwrap())
+    }
+
+    #[test]
+    fn test_diff_documents() {
+        let old = document(
+            r#"
+            typedef i64 ItemId
+            enum Status { ON = 1, OFF = 2 }
+            struct Item {
+                1: required i64 id,
+                2: optional string title,
+                3: optional i32 stock,
+                4: optional string note,
+                5: optional string desc,
+            }
+            service ItemService { Item GetItem (1: Item req) }
+            "#,
+        );
+        let new = document(
+            r#"
+            typedef i64 ItemId
+            enum Status { ON = 1, DELETED = 3 }
+            struct Item {
+                1: required ItemId id,
+                2: required string title,
+                3: optional string stock,
+                4: optional list<string> labels,
+                6: optional string extra,
+            }
+            service ItemApi { Item GetItem (1: Item req) }
+            "#,
+        );
+        let changes = diff_documents(&old, &new);
+        let find = |message: &str| {
+            changes
+                .iter()
+                .find(|c| c.message.contains(message))
+                .unwrap_or_else(|| panic!("未找到变化：{}\n{:#?}", message, changes))
+        };
+
+        assert!(find("服务 ItemService 改名为 ItemApi").breaking);
+        assert!(find("Item.title 由 optional 变为 required").breaking);
+        assert!(find("Item.stock 的类型由 i32 变为 string").breaking);
+        assert!(find("字段编号 4 被复用").breaking);
+        assert!(find("删除字段 Item.desc").breaking);
+        assert!(!find("新增字段 Item.extra").breaking);
+        assert!(find("删除枚举项 Status.OFF").breaking);
+        assert!(!find("新增枚举项 Status.DELETED").breaking);
+        assert!(!find("Item.id 的类型写法由 i64 变为 ItemId").breaking);
+    }
+
+    #[test]
+    fn test_diff_method_arguments() {
+        let old = document(
+            r#"
+            struct Req { 1: i64 id }
+            service S {
+                string Get (1: Req req, 2: i32 page),
+                void Ping (1: i64 id, 2: string note),
+            }
+            "#,
+        );
+        let new = document(
+            r#"
+            struct Req { 1: i64 id }
+            service S {
+                string Get (1: Req req, 2: string page, 3: required i64 extra),
+                void Ping (1: i64 id, 3: optional string trace),
+            }
+            "#,
+        );
+        let changes = diff_documents(&old, &new);
+        let find = |message: &str| {
+            changes
+                .iter()
+                .find(|c| c.message.contains(message))
+                .unwrap_or_else(|| panic!("未找到变化：{}\n{:#?}", message, changes))
+        };
+
+        let change = find("S.Get.page 的类型由 i32 变为 string");
+        assert!(change.breaking);
+        assert_eq!(change.target, "argument");
+        assert_eq!(change.path(), "S.Get.page");
+        assert!(find("新增 required 参数 S.Get.extra").breaking);
+        assert!(find("删除参数 S.Ping.note").breaking);
+        assert!(!find("新增参数 S.Ping.trace").breaking);
+        assert_eq!(changes.iter().filter(|c| c.breaking).count(), 3);
+    }
+}
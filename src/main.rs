@@ -1,15 +1,65 @@
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
-use std::fs::read_dir;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::thread;
+use std::time::Duration;
 
+use pilota_thrift_parser::File;
+use similar::TextDiff;
+use watcher::Watcher;
+
+mod changelog_generator;
+mod collection_generator;
+mod config;
+mod diagram_generator;
 mod idl_parser;
+mod ir;
+mod ir_diff;
 mod doc_generator;
+mod graphql_generator;
+mod html_generator;
+mod openapi_generator;
+mod payload_validator;
+mod proto_converter;
+mod resolver;
 mod sample_data_generator;
+mod schema_generator;
+mod server;
+mod source_map;
+mod typescript_generator;
+mod watcher;
+
+/// 生成文档的输出目录
+const OUT_DIR: &str = "volodoc";
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // 读取当前目录下的 volodoc.toml（不存在时使用默认配置）
+    let mut cfg = config::load_config(config::CONFIG_FILE).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+    // 命令行中的全局选项优先于配置文件
+    if take_flag(&mut args, "--diagrams") {
+        cfg.diagrams = true;
+    }
+    if let Some(mode) = take_option(&mut args, "--optional") {
+        cfg.sample.optional = mode;
+    }
+    if let Some(seed) = take_option(&mut args, "--seed") {
+        cfg.sample.seed = Some(seed.parse().unwrap_or_else(|_| {
+            eprintln!("--seed 需要一个非负整数：{}", seed);
+            process::exit(1);
+        }));
+    }
+    if let Err(e) = cfg.validate() {
+        eprintln!("配置有误: {}", e);
+        process::exit(1);
+    }
+    config::init(cfg);
 
     // 如果传入 --version，则输出版本号并退出
     if args.len() >= 2 && args[1] == "--version" {
@@ -21,12 +71,205 @@ fn main() {
     if args.len() >= 2 {
         match args[1].as_str() {
             "doc_generator" => {
-                if args.len() >= 3 {
+                let format = take_doc_format(&mut args);
+                let idl_paths = if args.len() >= 3 {
                     // 只处理用户指定的单个文件
-                    process_file(&args[2]);
+                    vec![args[2].clone()]
                 } else {
-                    // 未指定具体的文件时，扫描 idl/ 目录下所有 .thrift 文件处理
-                    process_directory("idl");
+                    // 未指定具体的文件时，扫描配置的 IDL 目录（默认 idl/）下所有 .thrift 文件处理
+                    collect_idl_files()
+                };
+                if format != "html" {
+                    for idl_path in &idl_paths {
+                        process_file(idl_path);
+                    }
+                }
+                if format != "markdown" {
+                    generate_html(&idl_paths);
+                }
+                return;
+            }
+            "export" => {
+                // 导出其它格式：volodoc export <格式> [IDL 文件]
+                let bundle = take_flag(&mut args, "--bundle");
+                let mut ts_options = config::get().typescript.clone();
+                if let Some(style) = take_option(&mut args, "--enum-style") {
+                    ts_options.enum_style = style;
+                }
+                if let Some(i64_type) = take_option(&mut args, "--i64") {
+                    ts_options.i64 = i64_type;
+                }
+                let Some(target) = args.get(2).cloned() else {
+                    eprintln!("用法：volodoc export <openapi|jsonschema|typescript|graphql|postman|insomnia> [IDL 文件] [--bundle]");
+                    process::exit(1);
+                };
+                let idl_paths = if args.len() >= 4 { vec![args[3].clone()] } else { collect_idl_files() };
+                match target.as_str() {
+                    "openapi" => export_files(&idl_paths, "_openapi.json", |idl_path| {
+                        let doc = resolver::load_resolved_document(idl_path)?;
+                        let samples = sample_data_generator::generate_document_samples(&doc);
+                        let spec = openapi_generator::generate_openapi(&doc, &file_stem(idl_path), &samples);
+                        Ok(serde_json::to_string_pretty(&spec).unwrap())
+                    }),
+                    // --bundle 时所有类型打包到一个文件的 $defs 中，否则每个类型一个文件
+                    "jsonschema" if bundle => export_files(&idl_paths, "_schema.json", |idl_path| {
+                        let doc = resolver::load_resolved_document(idl_path)?;
+                        let schema = schema_generator::bundled_schema(&doc, &file_stem(idl_path));
+                        Ok(serde_json::to_string_pretty(&schema).unwrap())
+                    }),
+                    "jsonschema" => export_json_schemas(&idl_paths),
+                    "graphql" => export_files(&idl_paths, ".graphql", |idl_path| {
+                        let doc = resolver::load_resolved_document(idl_path)?;
+                        Ok(graphql_generator::generate_graphql(&doc, &config::get().graphql))
+                    }),
+                    "postman" => export_files(&idl_paths, "_postman.json", |idl_path| {
+                        let doc = resolver::load_resolved_document(idl_path)?;
+                        let samples = sample_data_generator::generate_document_samples(&doc);
+                        let collection = collection_generator::generate_postman(
+                            &doc, &file_stem(idl_path), &samples, &config::get().collection);
+                        Ok(serde_json::to_string_pretty(&collection).unwrap())
+                    }),
+                    "insomnia" => export_files(&idl_paths, "_insomnia.json", |idl_path| {
+                        let doc = resolver::load_resolved_document(idl_path)?;
+                        let samples = sample_data_generator::generate_document_samples(&doc);
+                        let export = collection_generator::generate_insomnia(
+                            &doc, &file_stem(idl_path), &samples, &config::get().collection);
+                        Ok(serde_json::to_string_pretty(&export).unwrap())
+                    }),
+                    "typescript" => export_files(&idl_paths, "_types.ts", |idl_path| {
+                        let doc = resolver::load_resolved_document(idl_path)?;
+                        typescript_generator::generate_typescript(&doc, &file_stem(idl_path), &ts_options)
+                    }),
+                    _ => {
+                        eprintln!("不支持的导出格式：{}", target);
+                        process::exit(1);
+                    }
+                }
+                return;
+            }
+            "convert" => {
+                // IDL 格式转换：volodoc convert proto [IDL 文件]
+                match args.get(2).map(|s| s.as_str()) {
+                    Some("proto") => {}
+                    Some("thrift") => {
+                        eprintln!("暂不支持从 .proto 转换为 Thrift（尚未实现 Protobuf 解析）");
+                        process::exit(1);
+                    }
+                    _ => {
+                        eprintln!("用法：volodoc convert proto [IDL 文件]");
+                        process::exit(1);
+                    }
+                }
+                let idl_paths = if args.len() >= 4 { vec![args[3].clone()] } else { collect_idl_files() };
+                export_files(&idl_paths, ".proto", convert_to_proto);
+                return;
+            }
+            "diff" => {
+                // 比较两个版本的 IDL 文件或目录：volodoc diff <旧版本> <新版本>
+                let (Some(old), Some(new)) = (args.get(2), args.get(3)) else {
+                    eprintln!("用法：volodoc diff <旧 IDL 文件或目录> <新 IDL 文件或目录>");
+                    process::exit(1);
+                };
+                let diffs = ir_diff::diff_paths(old, new).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                });
+                let breaking = diffs.iter().flat_map(|d| &d.changes).filter(|c| c.breaking).count();
+                let total: usize = diffs.iter().map(|d| d.changes.len()).sum();
+                for diff in diffs.iter().filter(|d| !d.changes.is_empty()) {
+                    println!("{}:", diff.name);
+                    for change in &diff.changes {
+                        let tag = if change.breaking { "不兼容" } else { "兼容" };
+                        println!("  [{}] {}", tag, change.message);
+                    }
+                }
+                println!("共 {} 处变化，其中 {} 处不兼容", total, breaking);
+                if breaking > 0 {
+                    process::exit(1);
+                }
+                return;
+            }
+            "changelog" => {
+                // 生成两个版本之间的 API 变更记录：volodoc changelog <旧版本> <新版本> [--output 文件]
+                let output = take_option(&mut args, "--output");
+                let (Some(old), Some(new)) = (args.get(2), args.get(3)) else {
+                    eprintln!("用法：volodoc changelog <旧 IDL 文件或目录> <新 IDL 文件或目录> [--output 文件]");
+                    process::exit(1);
+                };
+                let diffs = ir_diff::diff_paths(old, new).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                });
+                let changelog = changelog_generator::generate_changelog(&diffs, old, new);
+                match output {
+                    Some(path) => {
+                        fs::write(&path, changelog)
+                            .unwrap_or_else(|e| { eprintln!("写入 {} 失败: {}", path, e); process::exit(1); });
+                        println!("变更记录已写入 {}", path);
+                    }
+                    None => print!("{}", changelog),
+                }
+                return;
+            }
+            "check" => {
+                // 检查已生成的文档是否为最新：volodoc check [IDL 文件] [--format markdown|html|all]
+                let format = take_doc_format(&mut args);
+                let idl_paths = if args.len() >= 3 { vec![args[2].clone()] } else { collect_idl_files() };
+                if !check(&idl_paths, &format) {
+                    process::exit(1);
+                }
+                return;
+            }
+            "validate" => {
+                // 按 IDL 校验 JSON：volodoc validate --method 服务名.方法名 [--response] <JSON 文件> [IDL 文件]
+                let method = take_option(&mut args, "--method");
+                let response = take_flag(&mut args, "--response");
+                let (Some(method), Some(payload_path)) = (method, args.get(2).cloned()) else {
+                    eprintln!("用法：volodoc validate --method <服务名.方法名> [--response] <JSON 文件> [IDL 文件]");
+                    process::exit(1);
+                };
+                let idl_paths = if args.len() >= 4 { vec![args[3].clone()] } else { collect_idl_files() };
+                match validate(&idl_paths, &method, response, &payload_path) {
+                    Ok(true) => {}
+                    Ok(false) => process::exit(1),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        process::exit(1);
+                    }
+                }
+                return;
+            }
+            "watch" => {
+                watch();
+                return;
+            }
+            "serve" => {
+                // 启动本地预览服务，端口可通过 --port 或配置文件指定
+                let serve_config = &config::get().serve;
+                let port = take_option(&mut args, "--port")
+                    .map(|p| p.parse::<u16>().unwrap_or_else(|_| {
+                        eprintln!("无效的端口：{}", p);
+                        process::exit(1);
+                    }))
+                    .unwrap_or(serve_config.port);
+                let addr = format!("{}:{}", serve_config.host, port);
+                if let Err(e) = server::serve(&addr) {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+                return;
+            }
+            "ir" => {
+                // 将解析结果以 JSON 或 YAML 格式输出到标准输出：volodoc ir [IDL 文件] [--format json|yaml]
+                let format = take_option(&mut args, "--format").unwrap_or_else(|| "json".to_string());
+                let idl_paths = if args.len() >= 3 { vec![args[2].clone()] } else { collect_idl_files() };
+                let output = ir::build_ir(&idl_paths).and_then(|ir| ir::render_ir(&ir, &format));
+                match output {
+                    Ok(output) => println!("{}", output),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        process::exit(1);
+                    }
                 }
                 return;
             }
@@ -37,72 +280,441 @@ fn main() {
         }
     }
 
-    // 未传入参数时，也默认遍历 idl 目录下所有 .thrift 文件
-    process_directory("idl");
+    // 未传入参数时，也默认遍历 IDL 目录下所有 .thrift 文件
+    for idl_path in collect_idl_files() {
+        process_file(&idl_path);
+    }
 }
 
-/// 遍历指定的目录，处理其中所有以 .thrift 为后缀的文件
-fn process_directory(idl_dir: &str) {
-    let out_dir = "volodoc"; // 输出目录
-    if !Path::new(out_dir).exists() {
-        fs::create_dir_all(out_dir).expect("创建 volodoc 文件夹失败");
-    }
-    
-    let entries = read_dir(idl_dir).unwrap_or_else(|_| {
-        eprintln!("读取 {} 目录失败", idl_dir);
+/// 取出文档输出格式 `--format`：markdown（默认）、html 或 all
+fn take_doc_format(args: &mut Vec<String>) -> String {
+    let format = take_option(args, "--format").unwrap_or_else(|| "markdown".to_string());
+    if !matches!(format.as_str(), "markdown" | "html" | "all") {
+        eprintln!("不支持的输出格式：{}（可选 markdown、html、all）", format);
         process::exit(1);
-    });
+    }
+    format
+}
 
-    for entry in entries {
-        let entry = entry.expect("读取目录项失败");
-        let path = entry.path();
-        if let Some(ext) = path.extension() {
-            if ext == "thrift" {
-                let idl_path = path.to_str().unwrap().to_string();
-                process_file(&idl_path);
-            }
+/// 从命令行参数中取出 `--name` 形式的开关，存在时返回 true
+fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+    match args.iter().position(|a| a == name) {
+        Some(pos) => {
+            args.remove(pos);
+            true
         }
+        None => false,
     }
 }
 
-/// 处理单个 IDL 文件，生成 API 文档和示例数据
-fn process_file(idl_path: &str) {
-    let content = fs::read_to_string(idl_path).unwrap_or_else(|_| {
-        eprintln!("读取 IDL 文件失败: {}", idl_path);
+/// 从命令行参数中取出 `--name value` 形式的选项，并将其从参数列表中移除
+fn take_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let pos = args.iter().position(|a| a == name)?;
+    if pos + 1 >= args.len() {
+        eprintln!("选项 {} 缺少参数值", name);
         process::exit(1);
-    });
+    }
+    let value = args.remove(pos + 1);
+    args.remove(pos);
+    Some(value)
+}
+
+/// 收集配置的 IDL 目录下所有以 .thrift 为后缀的文件路径
+fn collect_idl_files() -> Vec<String> {
+    config::get().idl_files().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    })
+}
 
-    let file = idl_parser::parse_idl(&content).unwrap_or_else(|e| {
+/// 为给定的 IDL 文件生成 HTML 文档站点，输出到 volodoc/html 目录
+fn generate_html(idl_paths: &[String]) {
+    let pages = html_generator::load_pages(idl_paths).unwrap_or_else(|e| {
         eprintln!("解析 IDL 失败: {}", e);
         process::exit(1);
     });
 
-    // 生成 API 文档（Markdown 格式）
-    let api_markdown = idl_parser::parse_handler(&file);
-    // 生成示例数据
-    let sample_data = sample_data_generator::generate_sample_data(&file);
+    let out_dir = format!("{}/html", OUT_DIR);
+    fs::create_dir_all(&out_dir).unwrap_or_else(|e| {
+        eprintln!("创建 {} 文件夹失败: {}", out_dir, e);
+        process::exit(1);
+    });
+    let files = html_generator::generate_site(&pages).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+    for (name, html) in files {
+        let out_path = format!("{}/{}", out_dir, name);
+        fs::write(&out_path, html)
+            .unwrap_or_else(|e| { eprintln!("写入 HTML 文档失败: {}", e); process::exit(1); });
+        println!("生成 HTML 文档: {}", out_path);
+    }
+}
 
-    // 获取输出文件夹并创建（如果不存在）
-    let out_dir = "volodoc";
-    if !Path::new(out_dir).exists() {
-        fs::create_dir_all(out_dir).unwrap_or_else(|e| {
+/// 在内存中重新生成文档并与磁盘上的文件比较，不写入任何文件
+/// 有文件缺失或内容不一致时打印 unified diff 并返回 false
+fn check(idl_paths: &[String], format: &str) -> bool {
+    let mut expected = Vec::new();
+    if format != "html" {
+        for idl_path in idl_paths {
+            let file = load_idl(idl_path).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                process::exit(1);
+            });
+            expected.extend(render_outputs(idl_path, &file, true, true));
+        }
+    }
+    if format != "markdown" {
+        let pages = html_generator::load_pages(idl_paths).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        });
+        let files = html_generator::generate_site(&pages).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        });
+        for (name, html) in files {
+            expected.push((format!("{}/html/{}", OUT_DIR, name), html));
+        }
+    }
+
+    let mut outdated = 0;
+    for (path, content) in &expected {
+        let Ok(actual) = fs::read_to_string(path) else {
+            println!("缺少文件: {}", path);
+            outdated += 1;
+            continue;
+        };
+        if &actual != content {
+            print!(
+                "{}",
+                TextDiff::from_lines(&actual, content)
+                    .unified_diff()
+                    .header(&format!("a/{}", path), &format!("b/{}", path))
+            );
+            outdated += 1;
+        }
+    }
+    if outdated > 0 {
+        println!("{} 个文件需要更新，请重新执行 volodoc doc_generator", outdated);
+        return false;
+    }
+    println!("{} 个文件均为最新", expected.len());
+    true
+}
+
+/// 为每个 IDL 文件调用 `generate` 生成导出内容，写入 `volodoc/{文件名}{suffix}`
+fn export_files(idl_paths: &[String], suffix: &str, generate: impl Fn(&str) -> Result<String, String>) {
+    fs::create_dir_all(OUT_DIR).unwrap_or_else(|e| {
+        eprintln!("创建 {} 文件夹失败: {}", OUT_DIR, e);
+        process::exit(1);
+    });
+    for idl_path in idl_paths {
+        let content = generate(idl_path).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        });
+        let out_path = format!("{}/{}{}", OUT_DIR, file_stem(idl_path), suffix);
+        fs::write(&out_path, content)
+            .unwrap_or_else(|e| { eprintln!("写入 {} 失败: {}", out_path, e); process::exit(1); });
+        println!("导出 {} 成功: {}", idl_path, out_path);
+    }
+}
+
+/// 将 Thrift 文件转换为 proto3，无法无损转换的内容打印为转换报告
+fn convert_to_proto(idl_path: &str) -> Result<String, String> {
+    let doc = resolver::load_resolved_document(idl_path)?;
+    let (_, includes) = resolver::load_document(Path::new(idl_path))?;
+    let dir = Path::new(idl_path).parent().unwrap_or(Path::new(""));
+    let mut imports = Vec::new();
+    for include in &includes {
+        let (included, _) = resolver::load_document(&dir.join(include))?;
+        imports.push(proto_converter::ProtoImport {
+            stem: file_stem(include),
+            package: included.namespace,
+        });
+    }
+    let source = Path::new(idl_path).file_name().unwrap().to_string_lossy();
+    let output = proto_converter::thrift_to_proto(&doc, &source, &imports);
+    if !output.report.is_empty() {
+        println!("{} 中有 {} 处无法无损转换：", idl_path, output.report.len());
+        for line in &output.report {
+            println!("  - {}", line);
+        }
+    }
+    Ok(output.proto)
+}
+
+/// 为每个类型导出一份 JSON Schema，写入 volodoc/{文件名}_schema/{类型名}.json
+fn export_json_schemas(idl_paths: &[String]) {
+    for idl_path in idl_paths {
+        let doc = resolver::load_resolved_document(idl_path).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        });
+        let out_dir = format!("{}/{}_schema", OUT_DIR, file_stem(idl_path));
+        fs::create_dir_all(&out_dir).unwrap_or_else(|e| {
             eprintln!("创建 {} 文件夹失败: {}", out_dir, e);
             process::exit(1);
         });
+        let schemas = schema_generator::type_schemas(&doc);
+        for (name, schema) in &schemas {
+            let out_path = format!("{}/{}.json", out_dir, name);
+            fs::write(&out_path, serde_json::to_string_pretty(schema).unwrap())
+                .unwrap_or_else(|e| { eprintln!("写入 {} 失败: {}", out_path, e); process::exit(1); });
+        }
+        println!("导出 {} 成功: {}（{} 个类型）", idl_path, out_dir, schemas.len());
     }
+}
 
-    let filename = Path::new(idl_path)
+/// 获取 IDL 文件名（不含后缀）
+fn file_stem(idl_path: &str) -> String {
+    Path::new(idl_path)
         .file_stem()
         .unwrap()
         .to_str()
-        .unwrap();
-    let api_out_path = format!("{}/{}_api.md", out_dir, filename);
-    let sample_out_path = format!("{}/{}_test.md", out_dir, filename);
+        .unwrap()
+        .to_string()
+}
+
+/// 处理单个 IDL 文件，生成 API 文档和示例数据
+fn process_file(idl_path: &str) {
+    let file = load_idl(idl_path).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+
+    // 获取输出文件夹并创建（如果不存在）
+    if !Path::new(OUT_DIR).exists() {
+        fs::create_dir_all(OUT_DIR).unwrap_or_else(|e| {
+            eprintln!("创建 {} 文件夹失败: {}", OUT_DIR, e);
+            process::exit(1);
+        });
+    }
+
+    let outputs = render_outputs(idl_path, &file, true, true);
+    for (out_path, content) in &outputs {
+        fs::write(out_path, content)
+            .unwrap_or_else(|e| { eprintln!("写入 {} 失败: {}", out_path, e); process::exit(1); });
+    }
+
+    let paths: Vec<&str> = outputs.iter().map(|(p, _)| p.as_str()).collect();
+    println!("处理 {} 成功，生成文件:\n  {}", idl_path, paths.join("\n  "));
+}
+
+/// 在 IDL 文件中查找方法并校验 JSON 文件，打印发现的问题，JSON 符合定义时返回 true
+fn validate(idl_paths: &[String], method_name: &str, response: bool, payload_path: &str) -> Result<bool, String> {
+    let content = fs::read_to_string(payload_path).map_err(|_| format!("读取 JSON 文件失败: {}", payload_path))?;
+    let payload: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| format!("{} 不是有效的 JSON: {}", payload_path, e))?;
+    for idl_path in idl_paths {
+        let doc = resolver::load_resolved_document(idl_path)?;
+        let Some(method) = payload_validator::find_method(&doc, method_name) else {
+            continue;
+        };
+        let target = if response { "返回值" } else { "参数" };
+        let problems = payload_validator::validate_payload(&doc, method, response, &payload);
+        if problems.is_empty() {
+            println!("{} 符合 {} 的{}定义（{}）", payload_path, method_name, target, idl_path);
+            return Ok(true);
+        }
+        for problem in &problems {
+            println!("{}", problem);
+        }
+        println!("{} 与 {} 的{}定义不符，共 {} 个问题（{}）", payload_path, method_name, target, problems.len(), idl_path);
+        return Ok(false);
+    }
+    Err(format!("在 IDL 文件中找不到方法 {}（格式为 服务名.方法名）", method_name))
+}
+
+/// 读取并解析单个 IDL 文件
+fn load_idl(idl_path: &str) -> Result<File, String> {
+    let content = fs::read_to_string(idl_path)
+        .map_err(|_| format!("读取 IDL 文件失败: {}", idl_path))?;
+    idl_parser::parse_idl(&content).map_err(|e| format!("{}: {}", idl_path, e))
+}
+
+/// 在内存中生成单个 IDL 文件对应的文档，返回 (输出路径, 内容) 列表
+/// `api` 控制是否生成 API 文档（依赖模板），`samples` 控制是否生成示例数据
+fn render_outputs(idl_path: &str, file: &File, api: bool, samples: bool) -> Vec<(String, String)> {
+    let filename = file_stem(idl_path);
+    let mut outputs = Vec::new();
+    if api {
+        // 生成 API 文档（Markdown 格式）
+        outputs.push((
+            format!("{}/{}_api.md", OUT_DIR, filename),
+            idl_parser::parse_handler(file),
+        ));
+    }
+    if samples {
+        // 生成示例数据和测试用例，递归类型被截断时提示，便于调整 sample.max_depth
+        let (values, truncated) = sample_data_generator::generate_samples(file, true);
+        if !truncated.is_empty() {
+            eprintln!(
+                "{} 的示例数据在递归类型 {} 处截断（sample.max_depth = {}）",
+                idl_path,
+                truncated.join(", "),
+                config::get().sample.max_depth
+            );
+        }
+        outputs.push((format!("{}/{}_test.md", OUT_DIR, filename), values.to_string()));
+    }
+    outputs
+}
+
+/// 持续监听 IDL 目录和模板目录，文件变化时只重新生成受影响的文档：
+/// IDL 文件变化时重新生成该文件以及（间接）include 了它的文件，模板变化时只重新生成 API 文档
+fn watch() {
+    let cfg = config::get();
+    let template_dir = cfg.template_dir.as_ref().map(PathBuf::from);
+    let mut watched: Vec<PathBuf> = cfg.idl_roots.iter().map(PathBuf::from).collect();
+    watched.extend(template_dir.clone());
+    let mut watcher = Watcher::new(watched);
+
+    // 每个 IDL 文件 include 的文件，用于查找受影响的文件
+    let mut includes: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    let idl_paths = cfg.idl_files().unwrap_or_default();
+    let all: HashSet<PathBuf> = idl_paths.iter().map(|p| normalize_path(Path::new(p))).collect();
+    regenerate(&idl_paths, &all, false, &mut includes);
+    println!("正在监听 {} 的变化，按 Ctrl+C 退出", cfg.idl_roots.join(", "));
+
+    loop {
+        thread::sleep(Duration::from_millis(500));
+        let changed = watcher.poll();
+        if changed.is_empty() {
+            continue;
+        }
+
+        let template_changed = changed
+            .iter()
+            .any(|p| template_dir.as_ref().is_some_and(|dir| p.starts_with(dir)));
+        let idl_paths = cfg.idl_files();
+        let mut affected: HashSet<PathBuf> = HashSet::new();
+        for path in &changed {
+            if path.extension().is_some_and(|ext| ext == "thrift") {
+                let key = normalize_path(path);
+                if path.exists() {
+                    println!("修改: {}", path.display());
+                } else {
+                    println!("删除: {}", path.display());
+                    includes.remove(&key);
+                    if let Ok(idl_paths) = &idl_paths {
+                        remove_outputs(path, idl_paths);
+                    }
+                }
+                affected.insert(key);
+            } else if template_changed {
+                println!("模板修改: {}", path.display());
+            }
+        }
+        // 沿着 include 关系反向查找，直到没有新的受影响文件
+        loop {
+            let dependents: Vec<PathBuf> = includes
+                .iter()
+                .filter(|(file, deps)| !affected.contains(*file) && deps.iter().any(|d| affected.contains(d)))
+                .map(|(file, _)| file.clone())
+                .collect();
+            if dependents.is_empty() {
+                break;
+            }
+            affected.extend(dependents);
+        }
 
-    fs::write(&api_out_path, api_markdown)
-        .unwrap_or_else(|e| { eprintln!("写入 API 文档失败: {}", e); process::exit(1); });
-    fs::write(&sample_out_path, sample_data)
-        .unwrap_or_else(|e| { eprintln!("写入示例数据失败: {}", e); process::exit(1); });
+        match idl_paths {
+            Ok(idl_paths) => regenerate(&idl_paths, &affected, template_changed, &mut includes),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
 
-    println!("处理 {} 成功，生成文件:\n  {}\n  {}", idl_path, api_out_path, sample_out_path);
-}
\ No newline at end of file
+/// 重新生成受影响的文件：`affected` 中的文件生成全部文档，
+/// `template_changed` 时其余文件只重新生成 API 文档；内容未变化的文件不会重写
+fn regenerate(
+    idl_paths: &[String],
+    affected: &HashSet<PathBuf>,
+    template_changed: bool,
+    includes: &mut HashMap<PathBuf, Vec<PathBuf>>,
+) {
+    if let Err(e) = fs::create_dir_all(OUT_DIR) {
+        eprintln!("创建 {} 文件夹失败: {}", OUT_DIR, e);
+        return;
+    }
+    let targets: Vec<(&String, PathBuf, bool)> = idl_paths
+        .iter()
+        .map(|p| {
+            let key = normalize_path(Path::new(p));
+            let full = affected.contains(&key);
+            (p, key, full)
+        })
+        .filter(|(_, _, full)| *full || template_changed)
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+    let names: Vec<&str> = targets.iter().map(|(p, _, _)| p.as_str()).collect();
+    println!("  重新生成: {}", names.join(", "));
+
+    let mut updated = Vec::new();
+    for (idl_path, key, full) in targets {
+        let file = match load_idl(idl_path) {
+            Ok(file) => file,
+            Err(e) => {
+                // 解析失败时只打印错误，继续监听
+                eprintln!("  {}", e);
+                continue;
+            }
+        };
+        let dir = Path::new(idl_path).parent().unwrap_or(Path::new(""));
+        includes.insert(
+            key,
+            idl_parser::collect_includes(&file)
+                .iter()
+                .map(|inc| normalize_path(&dir.join(inc)))
+                .collect(),
+        );
+
+        for (out_path, content) in render_outputs(idl_path, &file, true, full) {
+            if fs::read_to_string(&out_path).is_ok_and(|old| old == content) {
+                continue;
+            }
+            match fs::write(&out_path, content) {
+                Ok(()) => updated.push(out_path),
+                Err(e) => eprintln!("  写入 {} 失败: {}", out_path, e),
+            }
+        }
+    }
+    if updated.is_empty() {
+        println!("  文档无变化");
+    } else {
+        println!("  已更新: {}", updated.join(", "));
+    }
+}
+
+/// 删除已删除的 IDL 文件生成的文档，其它 IDL 文件生成同名文档时保留
+fn remove_outputs(deleted: &Path, idl_paths: &[String]) {
+    let stem = file_stem(&deleted.to_string_lossy());
+    if idl_paths.iter().any(|p| file_stem(p) == stem) {
+        return;
+    }
+    for suffix in ["_api.md", "_test.md"] {
+        let out_path = format!("{}/{}{}", OUT_DIR, stem, suffix);
+        if fs::remove_file(&out_path).is_ok() {
+            println!("  已删除: {}", out_path);
+        }
+    }
+}
+
+/// 规范化路径，便于比较 include 路径与目录扫描得到的路径；
+/// 已删除的文件无法直接规范化，改为规范化其所在目录再拼接文件名，与删除前得到的路径一致
+fn normalize_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path)
+        .ok()
+        .or_else(|| {
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            Some(fs::canonicalize(dir).ok()?.join(path.file_name()?))
+        })
+        .unwrap_or_else(|| path.to_path_buf())
+}
@@ -0,0 +1,275 @@
+use serde_json::{json, Map, Value};
+use crate::idl_parser::{find_annotation, Document, Field, Method};
+use crate::schema_generator::{definitions, type_schema};
+
+const REF_PREFIX: &str = "#/components/schemas/";
+
+/// 支持的 HTTP 注解，例如 `(api.get = '/item/:id')`
+const HTTP_METHODS: [&str; 7] = ["get", "post", "put", "delete", "patch", "head", "options"];
+
+/// 字段位置注解与 OpenAPI 参数位置的对应关系
+const PARAM_LOCATIONS: [(&str, &str); 4] = [
+    ("api.query", "query"),
+    ("api.path", "path"),
+    ("api.header", "header"),
+    ("api.cookie", "cookie"),
+];
+
+/// 根据 Document 生成 OpenAPI 3.1 文档
+///
+/// 每个服务方法对应一个 operation：方法带有 `api.get`、`api.post` 等注解时使用注解中的路径，
+/// 否则按照 `POST /{Service}/{Method}` 的约定生成。`samples` 为
+/// `sample_data_generator::generate_document_samples` 由同一 Document 生成的示例数据，用作请求与返回的 example。
+pub fn generate_openapi(doc: &Document, title: &str, samples: &Value) -> Value {
+    let mut paths = Map::new();
+    for service in &doc.services {
+        for method in &service.methods {
+            let sample = &samples[&service.name][&method.name];
+            let (http_method, path) = http_route(&service.name, method);
+            let path = openapi_path(&path);
+            let mut operation = json!({
+                "operationId": format!("{}_{}", service.name, method.name),
+                "tags": [service.name],
+                "responses": responses(method, &sample["response"]),
+            });
+            let (parameters, body) = split_request(method, &http_method, &sample["request"]);
+            if !parameters.is_empty() {
+                operation["parameters"] = Value::Array(parameters);
+            }
+            if let Some(body) = body {
+                operation["requestBody"] = body;
+            }
+
+            let item = paths.entry(path).or_insert_with(|| json!({}));
+            item[http_method.as_str()] = operation;
+        }
+    }
+
+    let mut info = json!({ "title": title, "version": "1.0.0" });
+    if !doc.namespace.is_empty() {
+        info["description"] = json!(format!("命名空间：{}", doc.namespace));
+    }
+    json!({
+        "openapi": "3.1.0",
+        "info": info,
+        "paths": paths,
+        "components": { "schemas": definitions(doc, REF_PREFIX) },
+    })
+}
+
+/// 获取方法对应的 HTTP 方法和路径，路径保留注解中的写法，例如 `/item/:id`
+///
+/// 没有 HTTP 注解的方法按照 `POST /{Service}/{Method}` 的约定生成。
+pub fn http_route(service: &str, method: &Method) -> (String, String) {
+    for http_method in HTTP_METHODS {
+        if let Some(path) = find_annotation(&method.annotations, &format!("api.{}", http_method)) {
+            return (http_method.to_string(), path.to_string());
+        }
+    }
+    ("post".to_string(), format!("/{}/{}", service, method.name))
+}
+
+/// 方法是否带有 `api.get`、`api.post` 等 HTTP 注解
+pub fn has_http_annotation(method: &Method) -> bool {
+    HTTP_METHODS
+        .iter()
+        .any(|m| find_annotation(&method.annotations, &format!("api.{}", m)).is_some())
+}
+
+/// 请求字段在 HTTP 请求中的位置
+pub struct FieldBinding<'a> {
+    pub field: &'a Field,
+    /// query、path、header、cookie 或 body
+    pub location: &'static str,
+    /// 参数名或请求体中的属性名
+    pub name: String,
+}
+
+/// 确定带 HTTP 注解的方法中每个请求字段的位置
+///
+/// 带 `api.query`、`api.path` 等注解的字段作为参数，其余字段作为请求体
+/// （GET、HEAD、DELETE 请求中未注解的字段作为 query 参数），`api.body` 可以重命名请求体中的属性。
+pub fn bind_fields<'a>(method: &'a Method, http_method: &str) -> Vec<FieldBinding<'a>> {
+    method
+        .request
+        .fields
+        .iter()
+        .map(|field| {
+            let body_name = find_annotation(&field.annotations, "api.body");
+            let (location, name) = param_location(field).unwrap_or_else(|| {
+                if body_name.is_none() && matches!(http_method, "get" | "head" | "delete") {
+                    ("query", field.name.clone())
+                } else {
+                    let name = body_name.filter(|n| !n.is_empty()).unwrap_or(&field.name);
+                    ("body", name.to_string())
+                }
+            });
+            FieldBinding { field, location, name }
+        })
+        .collect()
+}
+
+/// 将路径中的 `:id` 转换为 OpenAPI 的 `{id}`
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|seg| match seg.strip_prefix(':') {
+            Some(param) => format!("{{{}}}", param),
+            None => seg.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 字段在 HTTP 请求中的位置与名称，没有位置注解时返回 None
+fn param_location(field: &Field) -> Option<(&'static str, String)> {
+    PARAM_LOCATIONS.iter().find_map(|(key, location)| {
+        find_annotation(&field.annotations, key).map(|name| {
+            let name = if name.is_empty() { field.name.clone() } else { name.to_string() };
+            (*location, name)
+        })
+    })
+}
+
+/// 将请求结构体拆分为参数列表和请求体
+///
+/// 没有 HTTP 注解的方法整个请求参数作为 JSON 请求体；带有 HTTP 注解时按 `bind_fields` 拆分，
+/// 参数不是本文件中的结构体（基础类型、include 文件中的类型等）时同样整个作为请求体。
+fn split_request(method: &Method, http_method: &str, sample: &Value) -> (Vec<Value>, Option<Value>) {
+    let request = &method.request;
+    if request.name.is_empty() {
+        return (Vec::new(), None);
+    }
+    if !has_http_annotation(method) || request.fields.is_empty() {
+        let body = json_body(type_schema(&request.name, REF_PREFIX), sample);
+        return (Vec::new(), Some(body));
+    }
+
+    let mut parameters = Vec::new();
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    let mut example = Map::new();
+    for FieldBinding { field, location, name } in bind_fields(method, http_method) {
+        if location != "body" {
+            parameters.push(json!({
+                "name": name,
+                "in": location,
+                "required": location == "path" || field.attribute == "Required",
+                "schema": type_schema(&field.r#type, REF_PREFIX),
+            }));
+            continue;
+        }
+        properties.insert(name.clone(), type_schema(&field.r#type, REF_PREFIX));
+        if field.attribute == "Required" {
+            required.push(json!(name));
+        }
+        if let Some(value) = sample.get(&field.name) {
+            example.insert(name, value.clone());
+        }
+    }
+
+    let body = if properties.is_empty() {
+        None
+    } else {
+        let mut schema = json!({ "type": "object", "properties": properties });
+        if !required.is_empty() {
+            schema["required"] = Value::Array(required);
+        }
+        Some(json_body(schema, &Value::Object(example)))
+    };
+    (parameters, body)
+}
+
+fn json_body(schema: Value, example: &Value) -> Value {
+    let mut content = json!({ "schema": schema });
+    if !example.is_null() {
+        content["example"] = example.clone();
+    }
+    json!({ "required": true, "content": { "application/json": content } })
+}
+
+/// 正常返回对应 200，throws 声明的异常合并为 default 响应
+fn responses(method: &Method, sample: &Value) -> Value {
+    let mut ok = json!({ "description": "成功" });
+    if !method.response.name.is_empty() && method.response.name != "void" {
+        let mut content = json!({ "schema": type_schema(&method.response.name, REF_PREFIX) });
+        if !sample.is_null() {
+            content["example"] = sample.clone();
+        }
+        ok["content"] = json!({ "application/json": content });
+    }
+    let mut responses = json!({ "200": ok });
+    if !method.throws.is_empty() {
+        let names: Vec<&str> = method.throws.iter().map(|e| e.r#type.as_str()).collect();
+        let schemas: Vec<Value> = names.iter().map(|ty| type_schema(ty, REF_PREFIX)).collect();
+        responses["default"] = json!({
+            "description": format!("异常：{}", names.join("、")),
+            "content": { "application/json": { "schema": { "oneOf": schemas } } },
+        });
+    }
+    responses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl_parser::{build_document, parse_idl};
+    use crate::sample_data_generator::generate_document_samples;
+
+    const SAMPLE_THRIFT: &str = r#"
+        namespace rs volo.example
+
+        enum Status { ON = 1, OFF = 2 }
+
+        union Key {
+            1: i64 id,
+            2: string slug,
+        }
+
+        struct GetItemRequest {
+            1: required i64 id (api.path = 'id'),
+            2: optional string lang (api.query = 'lang'),
+        }
+
+        struct Item {
+            1: required i64 id,
+            2: optional Status status,
+        }
+
+        exception NotFound { 1: string message }
+
+        service ItemService {
+            Item GetItem (1: GetItemRequest req) throws (1: NotFound e) (api.get = '/item/:id'),
+            Item FindItem (1: Key key),
+            Item LatestItem (1: i64 shop_id),
+        }
+    "#;
+
+    #[test]
+    fn test_generate_openapi() {
+        let file = parse_idl(SAMPLE_THRIFT).unwrap();
+        let doc = build_document(&file);
+        let spec = generate_openapi(&doc, "item", &generate_document_samples(&doc));
+
+        assert_eq!(spec["openapi"], "3.1.0");
+        let get = &spec["paths"]["/item/{id}"]["get"];
+        assert_eq!(get["operationId"], "ItemService_GetItem");
+        assert_eq!(get["parameters"][0]["in"], "path");
+        assert_eq!(get["parameters"][1]["name"], "lang");
+        assert_eq!(get["parameters"][1]["required"], false);
+        assert!(get.get("requestBody").is_none());
+        assert_eq!(get["responses"]["200"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Item");
+        assert_eq!(get["responses"]["default"]["content"]["application/json"]["schema"]["oneOf"][0]["$ref"], "#/components/schemas/NotFound");
+
+        let find = &spec["paths"]["/ItemService/FindItem"]["post"];
+        assert_eq!(find["requestBody"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Key");
+        let latest = &spec["paths"]["/ItemService/LatestItem"]["post"]["requestBody"]["content"]["application/json"];
+        assert_eq!(latest["schema"]["type"], "integer");
+        assert_eq!(latest["example"], json!(1234567890123_i64));
+
+        let schemas = &spec["components"]["schemas"];
+        assert_eq!(schemas["Item"]["required"], json!(["id"]));
+        assert_eq!(schemas["Item"]["properties"]["status"]["$ref"], "#/components/schemas/Status");
+        assert_eq!(schemas["Status"]["enum"], json!([1, 2]));
+        assert_eq!(schemas["Key"]["oneOf"][1]["required"], json!(["slug"]));
+    }
+}
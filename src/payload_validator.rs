@@ -0,0 +1,469 @@
+use std::collections::HashMap;
+use std::fmt;
+use regex::Regex;
+use serde_json::Value;
+use crate::idl_parser::{parse_type, Constraints, Document, Enum, Method, Struct, TypeExpr};
+
+/// 校验发现的一个问题，`pointer` 为出问题的位置（JSON Pointer，根为空字符串）
+#[derive(Debug, PartialEq)]
+pub struct Problem {
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() { "（根）" } else { &self.pointer };
+        write!(f, "{}: {}", pointer, self.message)
+    }
+}
+
+/// 按 `服务名.方法名` 查找方法
+pub fn find_method<'a>(doc: &'a Document, full_name: &str) -> Option<&'a Method> {
+    let (service, method) = full_name.split_once('.')?;
+    doc.services
+        .iter()
+        .find(|s| s.name == service)?
+        .methods
+        .iter()
+        .find(|m| m.name == method)
+}
+
+/// 按方法的参数（`response` 为 true 时为返回值）定义校验 JSON，返回发现的所有问题
+///
+/// JSON 的结构与 `_test.md` 中的示例一致：参数为结构体时是该结构体的对象，否则直接是参数的值，
+/// 没有参数或返回 void 时为 null；方法有多个参数时为以参数名为 key 的对象，按参数的 required / optional 校验。检查缺少的必填字段、未知字段、类型不符、整数超出范围、无效的枚举值、
+/// union 设置了多个字段以及字段上的 vt.* 约束。i64 既可以是数字也可以是数字字符串。
+/// `doc` 应为展开 include 后的 Document，否则 include 文件中的类型无法校验。
+pub fn validate_payload(doc: &Document, method: &Method, response: bool, payload: &Value) -> Vec<Problem> {
+    let mut validator = Validator {
+        structs: doc.structs.iter().map(|s| (s.name.as_str(), s)).collect(),
+        enums: doc.enums.iter().map(|e| (e.name.as_str(), e)).collect(),
+        typedefs: doc.typedefs.iter().map(|t| (t.name.as_str(), t.r#type.as_str())).collect(),
+        problems: Vec::new(),
+    };
+    if !response && method.arguments.len() > 1 {
+        // 多个参数视为一个以参数名为字段的结构体
+        let arguments = Struct {
+            name: format!("{} 的参数", method.name),
+            fields: method.arguments.clone(),
+            ..Default::default()
+        };
+        match payload.as_object() {
+            Some(map) => validator.check_struct(map, &arguments, ""),
+            None => validator.report("", format!("类型不符：应为以参数名为 key 的对象，实际为 {}", json_kind(payload))),
+        }
+        return validator.problems;
+    }
+    let ty = if response { &method.response.name } else { &method.request.name };
+    match ty.as_str() {
+        "" | "void" => {
+            if !payload.is_null() {
+                let what = if response { "方法返回 void" } else { "方法没有参数" };
+                validator.report("", format!("{}，应为 null", what));
+            }
+        }
+        ty => validator.check(payload, &parse_type(ty), "", ""),
+    }
+    validator.problems
+}
+
+struct Validator<'a> {
+    structs: HashMap<&'a str, &'a Struct>,
+    enums: HashMap<&'a str, &'a Enum>,
+    typedefs: HashMap<&'a str, &'a str>,
+    problems: Vec<Problem>,
+}
+
+impl Validator<'_> {
+    fn report(&mut self, pointer: &str, message: String) {
+        self.problems.push(Problem { pointer: pointer.to_string(), message });
+    }
+
+    fn resolve(&self, ty: &TypeExpr) -> TypeExpr {
+        match ty {
+            TypeExpr::Named(name) if self.typedefs.contains_key(name.as_str()) => {
+                self.resolve(&parse_type(self.typedefs[name.as_str()]))
+            }
+            _ => ty.clone(),
+        }
+    }
+
+    /// 校验 value 是否符合类型 ty，`type_name` 为报错时展示的类型写法（为空时由 ty 得到）
+    fn check(&mut self, value: &Value, ty: &TypeExpr, type_name: &str, pointer: &str) {
+        let type_name = if type_name.is_empty() { display_type(ty) } else { type_name.to_string() };
+        let mismatch = |this: &mut Self| {
+            this.report(pointer, format!("类型不符：应为 {}，实际为 {}", type_name, json_kind(value)));
+        };
+        let resolved = self.resolve(ty);
+        match &resolved {
+            TypeExpr::Base(base) => match base.as_str() {
+                "bool" if !value.is_boolean() => mismatch(self),
+                "byte" | "i8" | "i16" | "i32" | "i64" => match integer(value, base) {
+                    Some(Some(n)) if !in_int_range(base, n) => {
+                        self.report(pointer, format!("{} 超出 {} 的取值范围", n, base));
+                    }
+                    Some(Some(_)) => {}
+                    Some(None) => self.report(pointer, format!("{} 超出 {} 的取值范围", value, base)),
+                    None => mismatch(self),
+                },
+                "double" if !value.is_number() => mismatch(self),
+                "string" | "binary" if !value.is_string() => mismatch(self),
+                "uuid" => match value.as_str() {
+                    Some(s) if !is_uuid(s) => self.report(pointer, format!("\"{}\" 不是有效的 UUID", s)),
+                    Some(_) => {}
+                    None => mismatch(self),
+                },
+                _ => {}
+            },
+            TypeExpr::List(inner) | TypeExpr::Set(inner) => {
+                let Some(items) = value.as_array() else {
+                    return mismatch(self);
+                };
+                for (i, item) in items.iter().enumerate() {
+                    self.check(item, inner, "", &format!("{}/{}", pointer, i));
+                }
+                if matches!(resolved, TypeExpr::Set(_)) {
+                    for (i, item) in items.iter().enumerate() {
+                        if items[..i].contains(item) {
+                            self.report(&format!("{}/{}", pointer, i), "set 中有重复元素".to_string());
+                        }
+                    }
+                }
+            }
+            TypeExpr::Map(key_type, value_type) => {
+                let Some(map) = value.as_object() else {
+                    return mismatch(self);
+                };
+                for (key, item) in map {
+                    let item_pointer = format!("{}/{}", pointer, escape_pointer(key));
+                    if !self.valid_key(key, key_type) {
+                        self.report(&item_pointer, format!("map 的 key \"{}\" 不是有效的 {}", key, display_type(key_type)));
+                    }
+                    self.check(item, value_type, "", &item_pointer);
+                }
+            }
+            TypeExpr::Named(name) => {
+                if let Some(&s) = self.structs.get(name.as_str()) {
+                    match value.as_object() {
+                        Some(map) => self.check_struct(map, s, pointer),
+                        None => mismatch(self),
+                    }
+                } else if let Some(&e) = self.enums.get(name.as_str()) {
+                    // Thrift JSON 协议中枚举以整数值表示
+                    match value.as_i64() {
+                        Some(n) if !e.values.iter().any(|v| v.value == n) => {
+                            let allowed: Vec<String> = e.values.iter().map(|v| format!("{}（{}）", v.value, v.name)).collect();
+                            self.report(pointer, format!("无效的枚举值 {}，可选值为 {}", n, allowed.join("、")));
+                        }
+                        Some(_) => {}
+                        None => mismatch(self),
+                    }
+                }
+                // 找不到定义的类型无法校验
+            }
+        }
+    }
+
+    fn check_struct(&mut self, map: &serde_json::Map<String, Value>, s: &Struct, pointer: &str) {
+        for field in &s.fields {
+            let field_pointer = format!("{}/{}", pointer, escape_pointer(&field.name));
+            match map.get(&field.name) {
+                None if field.attribute == "Required" => self.report(&field_pointer, "缺少必填字段".to_string()),
+                None => {}
+                // null 表示未设置该字段
+                Some(Value::Null) if field.attribute == "Required" => {
+                    self.report(&field_pointer, "必填字段不能为 null".to_string());
+                }
+                Some(Value::Null) => {}
+                Some(value) => {
+                    let before = self.problems.len();
+                    self.check(value, &parse_type(&field.r#type), &field.r#type, &field_pointer);
+                    // 类型正确时才检查约束
+                    if self.problems.len() == before {
+                        self.check_constraints(value, &field.constraints, &parse_type(&field.r#type), &field_pointer);
+                    }
+                }
+            }
+        }
+        for key in map.keys() {
+            if !s.fields.iter().any(|f| &f.name == key) {
+                self.report(&format!("{}/{}", pointer, escape_pointer(key)), format!("未知字段，{} 中没有该字段", s.name));
+            }
+        }
+        if s.kind == "union" {
+            let set: Vec<&str> = s
+                .fields
+                .iter()
+                .filter(|f| map.get(&f.name).is_some_and(|v| !v.is_null()))
+                .map(|f| f.name.as_str())
+                .collect();
+            if set.len() > 1 {
+                self.report(pointer, format!("union 只能设置一个字段，实际设置了 {}", set.join("、")));
+            }
+        }
+    }
+
+    /// 校验字段上的 vt.* 约束
+    fn check_constraints(&mut self, value: &Value, c: &Constraints, ty: &TypeExpr, pointer: &str) {
+        let ty = &self.resolve(ty);
+        if !c.r#in.is_empty() && !c.r#in.iter().any(|raw| self.allows(raw, value, ty)) {
+            self.report(pointer, format!("{} 不在可选值 {} 中", value, c.r#in.join("、")));
+        }
+        let size = match value {
+            Value::String(s) => Some(("长度", s.chars().count())),
+            Value::Array(items) => Some(("元素个数", items.len())),
+            Value::Object(map) => Some(("元素个数", map.len())),
+            _ => None,
+        };
+        if let Some((what, len)) = size {
+            if let Some(min) = c.min_size
+                && len < min
+            {
+                self.report(pointer, format!("{} {} 小于最小值 {}", what, len, min));
+            }
+            if let Some(max) = c.max_size
+                && len > max
+            {
+                self.report(pointer, format!("{} {} 超过最大值 {}", what, len, max));
+            }
+        }
+        let i64_string = value.as_str().filter(|_| matches!(ty, TypeExpr::Base(base) if base == "i64"));
+        let number = value.as_f64().or_else(|| i64_string.and_then(|s| s.parse().ok()));
+        if let Some(n) = number {
+            let violated = [
+                (">", c.gt, c.gt.is_some_and(|b| n <= b)),
+                ("≥", c.ge, c.ge.is_some_and(|b| n < b)),
+                ("<", c.lt, c.lt.is_some_and(|b| n >= b)),
+                ("≤", c.le, c.le.is_some_and(|b| n > b)),
+            ];
+            for (op, bound, failed) in violated {
+                if let Some(bound) = bound
+                    && failed
+                {
+                    self.report(pointer, format!("取值 {} 不满足 {} {}", n, op, bound));
+                }
+            }
+        }
+        if let (Some(pattern), Some(s)) = (&c.pattern, value.as_str())
+            && let Ok(re) = Regex::new(pattern)
+            && !re.is_match(s)
+        {
+            self.report(pointer, format!("\"{}\" 不匹配 {}", s, pattern));
+        }
+    }
+
+    /// vt.in 中的一个取值是否与 value 相同：字符串可以带引号，枚举可以写枚举项名称
+    fn allows(&self, raw: &str, value: &Value, ty: &TypeExpr) -> bool {
+        let unquoted = raw.trim_matches(|c| c == '"' || c == '\'');
+        if let TypeExpr::Named(name) = self.resolve(ty)
+            && let Some(e) = self.enums.get(name.as_str())
+        {
+            let value_name = unquoted.rsplit('.').next().unwrap_or_default();
+            if let Some(v) = e.values.iter().find(|v| v.name == value_name) {
+                return value.as_i64() == Some(v.value);
+            }
+        }
+        match value {
+            Value::String(s) => s == unquoted,
+            Value::Number(n) => raw.parse::<f64>().ok() == n.as_f64(),
+            Value::Bool(b) => raw.parse::<bool>().ok() == Some(*b),
+            _ => false,
+        }
+    }
+
+    /// JSON 对象的 key 是否可以转换为 map 的 key 类型
+    fn valid_key(&self, key: &str, ty: &TypeExpr) -> bool {
+        match self.resolve(ty) {
+            TypeExpr::Base(base) => match base.as_str() {
+                "bool" => key == "true" || key == "false",
+                "byte" | "i8" | "i16" | "i32" | "i64" => key.parse().is_ok_and(|n| in_int_range(&base, n)),
+                "double" => key.parse::<f64>().is_ok(),
+                "uuid" => is_uuid(key),
+                _ => true,
+            },
+            TypeExpr::Named(name) => match self.enums.get(name.as_str()) {
+                Some(e) => key.parse::<i64>().is_ok_and(|n| e.values.iter().any(|v| v.value == n)),
+                None => true,
+            },
+            _ => true,
+        }
+    }
+}
+
+/// 整数值：不是整数时返回 None，是整数但超出 i64 范围时返回 Some(None)；
+/// 只有 i64 可以用数字字符串表示（与按字符串传输 i64 的服务约定一致）
+fn integer(value: &Value, base: &str) -> Option<Option<i64>> {
+    match value {
+        Value::Number(n) if n.is_i64() => Some(n.as_i64()),
+        Value::Number(n) if n.is_u64() => Some(None),
+        Value::String(s) if base == "i64" && is_integer_string(s) => Some(s.parse().ok()),
+        _ => None,
+    }
+}
+
+/// 可选的负号加上至少一位数字，例如 `-12`；`-`、`--5`、`+5` 都不是
+fn is_integer_string(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn in_int_range(base: &str, n: i64) -> bool {
+    match base {
+        "byte" | "i8" => i8::try_from(n).is_ok(),
+        "i16" => i16::try_from(n).is_ok(),
+        "i32" => i32::try_from(n).is_ok(),
+        _ => true,
+    }
+}
+
+fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.char_indices().all(|(i, c)| if matches!(i, 8 | 13 | 18 | 23) { c == '-' } else { c.is_ascii_hexdigit() })
+}
+
+/// JSON 值的类型名，用于报错
+fn json_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "布尔值",
+        Value::Number(n) if n.is_f64() => "小数",
+        Value::Number(_) => "整数",
+        Value::String(_) => "字符串",
+        Value::Array(_) => "数组",
+        Value::Object(_) => "对象",
+    }
+}
+
+/// TypeExpr 的 IDL 写法，例如 `list<Item>`
+fn display_type(ty: &TypeExpr) -> String {
+    match ty {
+        TypeExpr::Base(name) | TypeExpr::Named(name) => name.clone(),
+        TypeExpr::List(inner) => format!("list<{}>", display_type(inner)),
+        TypeExpr::Set(inner) => format!("set<{}>", display_type(inner)),
+        TypeExpr::Map(key, value) => format!("map<{}, {}>", display_type(key), display_type(value)),
+    }
+}
+
+/// JSON Pointer 中 `~` 和 `/` 需要转义
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl_parser::{build_document, parse_idl};
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_payload() {
+        let doc = build_document(
+            &parse_idl(
+                r#"
+                enum Status { ON = 1, OFF = 2 }
+                typedef i64 ItemId
+                union Key { 1: i64 id, 2: string code }
+                struct Item {
+                    1: required ItemId id,
+                    2: required string title (vt.max_size = "5"),
+                    3: optional Status status,
+                    4: optional list<i8> levels,
+                    5: optional map<i32, string> names,
+                    6: optional Key key,
+                    7: optional set<string> tags,
+                    8: optional i32 count,
+                }
+                service ItemService {
+                    Item GetItem (1: Item req),
+                    void Ping (),
+                    Item Search (1: Key key, 2: i32 page (vt.ge = "1"), 3: optional string keyword),
+                }
+                "#,
+            )
+            .unwrap(),
+        );
+        let method = find_method(&doc, "ItemService.GetItem").unwrap();
+        assert!(find_method(&doc, "ItemService.Missing").is_none());
+
+        let valid = json!({"id": "1234567890123", "title": "abc", "status": 2, "levels": [1, -128], "names": {"1": "a"}});
+        assert_eq!(validate_payload(&doc, method, false, &valid), vec![]);
+
+        let payload = json!({
+            "title": 12,
+            "status": 3,
+            "levels": [1, 300, "x"],
+            "names": {"a/b": "x"},
+            "key": {"id": 1, "code": "c"},
+            "tags": ["a", "a"],
+            "extra": true,
+        });
+        let problems: Vec<String> = validate_payload(&doc, method, false, &payload).iter().map(|p| p.to_string()).collect();
+        assert_eq!(
+            problems,
+            vec![
+                "/id: 缺少必填字段",
+                "/title: 类型不符：应为 string，实际为 整数",
+                "/status: 无效的枚举值 3，可选值为 1（ON）、2（OFF）",
+                "/levels/1: 300 超出 i8 的取值范围",
+                "/levels/2: 类型不符：应为 i8，实际为 字符串",
+                "/names/a~1b: map 的 key \"a/b\" 不是有效的 i32",
+                "/key: union 只能设置一个字段，实际设置了 id、code",
+                "/tags/1: set 中有重复元素",
+                "/extra: 未知字段，Item 中没有该字段",
+            ]
+        );
+
+        let problems = validate_payload(&doc, method, false, &json!({"id": 1, "title": "abcdef"}));
+        assert_eq!(problems[0].to_string(), "/title: 长度 6 超过最大值 5");
+
+        // 只有 i64 可以是数字字符串，字符串必须是完整的整数，超出范围的整数字符串报告超出范围
+        let problems: Vec<String> = ["-", "--5", "1x", "99999999999999999999"]
+            .iter()
+            .flat_map(|id| validate_payload(&doc, method, false, &json!({"id": id, "title": "a"})))
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "/id: 类型不符：应为 ItemId，实际为 字符串",
+                "/id: 类型不符：应为 ItemId，实际为 字符串",
+                "/id: 类型不符：应为 ItemId，实际为 字符串",
+                "/id: \"99999999999999999999\" 超出 i64 的取值范围",
+            ]
+        );
+        let payload = json!({"id": "-12", "title": "a", "names": {"1": "a"}, "levels": ["1"], "count": "5"});
+        let problems: Vec<String> = validate_payload(&doc, method, false, &payload).iter().map(|p| p.to_string()).collect();
+        assert_eq!(
+            problems,
+            vec!["/levels/0: 类型不符：应为 i8，实际为 字符串", "/count: 类型不符：应为 i32，实际为 字符串"]
+        );
+        let problems = validate_payload(&doc, method, true, &json!([]));
+        assert_eq!(problems[0].to_string(), "（根）: 类型不符：应为 Item，实际为 数组");
+
+        let ping = find_method(&doc, "ItemService.Ping").unwrap();
+        assert_eq!(validate_payload(&doc, ping, false, &Value::Null), vec![]);
+        assert_eq!(validate_payload(&doc, ping, true, &json!(1))[0].message, "方法返回 void，应为 null");
+
+        // 多个参数时为以参数名为 key 的对象
+        let search = find_method(&doc, "ItemService.Search").unwrap();
+        assert_eq!(validate_payload(&doc, search, false, &json!({"key": {"id": 1}, "page": 1})), vec![]);
+        let problems: Vec<String> = validate_payload(&doc, search, false, &json!({"key": {"id": 1, "code": "a"}, "page": 0, "extra": 1}))
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "/key: union 只能设置一个字段，实际设置了 id、code",
+                "/page: 取值 0 不满足 ≥ 1",
+                "/extra: 未知字段，Search 的参数 中没有该字段",
+            ]
+        );
+        assert_eq!(validate_payload(&doc, search, false, &json!({"page": 1}))[0].to_string(), "/key: 缺少必填字段");
+        assert_eq!(
+            validate_payload(&doc, search, false, &json!(1))[0].message,
+            "类型不符：应为以参数名为 key 的对象，实际为 整数"
+        );
+    }
+}
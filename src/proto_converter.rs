@@ -0,0 +1,527 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::idl_parser::{parse_type, Document, Enum, Field, Method, Service, Struct, TypeExpr};
+
+const INDENT: &str = "  ";
+
+/// 被 include 的文件转换后对应的 proto 文件
+pub struct ProtoImport {
+    /// include 文件名（不含后缀），即 IDL 中引用其类型时的前缀
+    pub stem: String,
+    /// 该文件的命名空间，作为 proto 的 package
+    pub package: String,
+}
+
+/// 转换结果：proto 文件内容以及无法无损转换的内容说明
+pub struct ProtoOutput {
+    pub proto: String,
+    pub report: Vec<String>,
+}
+
+/// 将 Thrift 文件转换为 proto3
+///
+/// `doc` 为 `resolver::load_resolved_document` 加载的 Document，只转换入口文件中的定义，
+/// 被 include 的类型通过 `import` 引用（对应的 `.proto` 需要单独转换）。
+/// 无法在 proto3 中表达的内容（required、set、throws、typedef、嵌套容器等）会按最接近的方式转换，
+/// 并在报告中说明。
+pub fn thrift_to_proto(doc: &Document, source: &str, imports: &[ProtoImport]) -> ProtoOutput {
+    let mut converter = Converter {
+        typedefs: doc.typedefs.iter().map(|t| (t.name.clone(), t.r#type.clone())).collect(),
+        enums: doc.enums.iter().map(|e| e.name.clone()).collect(),
+        defined: local_names(doc),
+        imports,
+        wrappers: BTreeMap::new(),
+        report: Vec::new(),
+        use_empty: false,
+    };
+
+    let mut blocks = Vec::new();
+    for e in doc.enums.iter().filter(|e| is_local(&e.name)) {
+        blocks.push(converter.enum_block(e));
+    }
+    converter.report_enum_value_conflicts(doc);
+    for s in doc.structs.iter().filter(|s| is_local(&s.name)) {
+        blocks.push(converter.message_block(s));
+    }
+    for t in doc.typedefs.iter().filter(|t| is_local(&t.name)) {
+        converter.report.push(format!("typedef {}：proto 不支持类型别名，引用处直接使用 {}", t.name, t.r#type));
+    }
+    let mut service_blocks = Vec::new();
+    for service in &doc.services {
+        service_blocks.push(converter.service_block(service));
+    }
+    blocks.extend(
+        converter
+            .wrappers
+            .iter()
+            .map(|(name, field)| format!("message {} {{\n{}{}\n}}", name, INDENT, field)),
+    );
+    blocks.extend(service_blocks);
+
+    let mut lines = vec![
+        format!("// 由 volodoc 根据 {} 转换生成", source),
+        "syntax = \"proto3\";".to_string(),
+    ];
+    if !doc.namespace.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("package {};", doc.namespace));
+    }
+    let mut import_lines: Vec<String> = imports.iter().map(|i| format!("import \"{}.proto\";", i.stem)).collect();
+    if converter.use_empty {
+        import_lines.push("import \"google/protobuf/empty.proto\";".to_string());
+    }
+    if !import_lines.is_empty() {
+        lines.push(String::new());
+        lines.extend(import_lines);
+    }
+    for block in blocks {
+        lines.push(String::new());
+        lines.push(block);
+    }
+    ProtoOutput {
+        proto: lines.join("\n") + "\n",
+        report: converter.report,
+    }
+}
+
+/// 入口文件中的定义不带 `文件名.` 前缀
+fn is_local(name: &str) -> bool {
+    !name.contains('.')
+}
+
+/// 入口文件中定义的消息、枚举和服务名称，生成的包装消息不能与之重名
+fn local_names(doc: &Document) -> HashSet<String> {
+    let structs = doc.structs.iter().map(|s| &s.name);
+    let enums = doc.enums.iter().map(|e| &e.name);
+    let services = doc.services.iter().map(|s| &s.name);
+    structs.chain(enums).chain(services).filter(|name| is_local(name)).cloned().collect()
+}
+
+struct Converter<'a> {
+    typedefs: HashMap<String, String>,
+    enums: Vec<String>,
+    /// 入口文件中已定义的名称
+    defined: HashSet<String>,
+    imports: &'a [ProtoImport],
+    /// 生成的包装消息，key 为消息名，value 为其字段（多个字段以换行和缩进分隔）
+    wrappers: BTreeMap<String, String>,
+    report: Vec<String>,
+    /// 是否用到了 google.protobuf.Empty
+    use_empty: bool,
+}
+
+impl Converter<'_> {
+    fn enum_block(&mut self, e: &Enum) -> String {
+        let mut lines = comment_lines(&e.comment, "");
+        lines.push(format!("enum {} {{", e.name));
+        if !e.values.iter().any(|v| v.value == 0) {
+            let name = format!("{}_UNSPECIFIED", upper_snake(&e.name));
+            self.report.push(format!("enum {}：proto3 枚举的第一个值必须为 0，已添加 {} = 0", e.name, name));
+            lines.push(format!("{}{} = 0;", INDENT, name));
+        }
+        let mut values: Vec<_> = e.values.iter().collect();
+        // 值为 0 的枚举项必须放在第一个
+        values.sort_by_key(|v| v.value != 0);
+        for v in values {
+            if i32::try_from(v.value).is_err() {
+                self.report.push(format!("enum {}.{}：值 {} 超出 proto 枚举的 int32 范围", e.name, v.name, v.value));
+            }
+            lines.extend(comment_lines(&v.comment, INDENT));
+            lines.push(format!("{}{} = {};", INDENT, v.name, v.value));
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    /// proto3 的枚举值与枚举类型处于同一作用域，同一 package 中不同枚举的值不能同名
+    fn report_enum_value_conflicts(&mut self, doc: &Document) {
+        let mut owners: HashMap<&str, &str> = HashMap::new();
+        for e in doc.enums.iter().filter(|e| is_local(&e.name)) {
+            for v in &e.values {
+                match owners.get(v.name.as_str()) {
+                    Some(owner) if *owner != e.name => self.report.push(format!(
+                        "enum {}.{}：与 enum {} 中的值同名，proto 中同一 package 的枚举值不能重名，需要手动重命名",
+                        e.name, v.name, owner
+                    )),
+                    _ => {
+                        owners.insert(&v.name, &e.name);
+                    }
+                }
+            }
+        }
+    }
+
+    fn message_block(&mut self, s: &Struct) -> String {
+        let mut lines = comment_lines(&s.comment, "");
+        lines.push(format!("message {} {{", s.name));
+        if s.kind == "union" {
+            lines.push(format!("{}oneof value {{", INDENT));
+            for f in &s.fields {
+                let ctx = format!("{}.{}", s.name, f.name);
+                let ty = self.single_type(&self.resolve(&f.r#type), &ctx);
+                let indent = INDENT.repeat(2);
+                lines.extend(comment_lines(&f.comment, &indent));
+                lines.push(format!("{}{} {} = {};", indent, ty, f.name, self.field_number(f, &ctx)));
+            }
+            lines.push(format!("{}}}", INDENT));
+        } else {
+            for f in &s.fields {
+                let ctx = format!("{}.{}", s.name, f.name);
+                lines.extend(comment_lines(&f.comment, INDENT));
+                lines.push(format!("{}{};", INDENT, self.field(f, &ctx)));
+            }
+        }
+        lines.push("}".to_string());
+        self.report_annotations(&s.name, s.fields.iter().flat_map(|f| &f.annotations).map(|a| &a.key));
+        lines.join("\n")
+    }
+
+    /// 普通消息中的字段，例如 `repeated string tags = 2`
+    fn field(&mut self, f: &Field, ctx: &str) -> String {
+        let number = self.field_number(f, ctx);
+        let expr = self.resolve(&f.r#type);
+        let ty = match &expr {
+            TypeExpr::List(value) | TypeExpr::Set(value) => {
+                if matches!(expr, TypeExpr::Set(_)) {
+                    self.report.push(format!("{}：set 转换为 repeated，不再保证元素唯一", ctx));
+                }
+                format!("repeated {}", self.single_type(value, ctx))
+            }
+            TypeExpr::Map(key, value) => {
+                format!("map<{}, {}>", self.map_key(key, ctx), self.single_type(value, ctx))
+            }
+            _ => {
+                let ty = self.single_type(&expr, ctx);
+                if f.attribute == "Optional" { format!("optional {}", ty) } else { ty }
+            }
+        };
+        if f.attribute == "Required" {
+            self.report.push(format!("{}：proto3 不支持 required，转换为普通字段", ctx));
+        }
+        format!("{} {} = {}", ty, f.name, number)
+    }
+
+    /// Thrift 字段编号可以为 0 或负数，proto 中必须为正数且不能使用保留区间
+    fn field_number(&mut self, f: &Field, ctx: &str) -> i32 {
+        if f.id < 1 || (19000..=19999).contains(&f.id) {
+            self.report.push(format!("{}：字段编号 {} 在 proto 中不可用，请手动指定", ctx, f.id));
+        }
+        f.id
+    }
+
+    /// 不能带 repeated / map 的位置（容器元素、oneof 分支、方法参数）使用的类型，嵌套容器转换为包装消息
+    fn single_type(&mut self, expr: &TypeExpr, ctx: &str) -> String {
+        let expr = &self.resolve_expr(expr);
+        match expr {
+            TypeExpr::Base(base) => self.scalar(base, ctx),
+            TypeExpr::Named(name) => self.message_name(name),
+            TypeExpr::List(value) | TypeExpr::Set(value) => {
+                let element = self.single_type(value, ctx);
+                let name = format!("{}List", wrapper_part(&element));
+                self.add_wrapper(&name, format!("repeated {} values = 1;", element), expr, ctx)
+            }
+            TypeExpr::Map(key, value) => {
+                let key = self.map_key(key, ctx);
+                let value = self.single_type(value, ctx);
+                let name = format!("{}{}Map", wrapper_part(&key), wrapper_part(&value));
+                self.add_wrapper(&name, format!("map<{}, {}> values = 1;", key, value), expr, ctx)
+            }
+        }
+    }
+
+    fn add_wrapper(&mut self, name: &str, field: String, expr: &TypeExpr, ctx: &str) -> String {
+        let name = self.wrapper_name(name, field, ctx);
+        self.report.push(format!("{}：{} 不能直接在此处使用，转换为包装消息 {}", ctx, type_string(expr), name));
+        name
+    }
+
+    /// 登记包装消息并返回其名称：字段相同的包装消息共用，
+    /// 名称已被入口文件中的定义或其它包装消息占用时依次追加数字后缀
+    fn wrapper_name(&mut self, name: &str, field: String, ctx: &str) -> String {
+        let mut unique = name.to_string();
+        for n in 2.. {
+            match self.wrappers.get(&unique) {
+                Some(existing) if *existing == field => return unique,
+                None if !self.defined.contains(&unique) => break,
+                _ => unique = format!("{}{}", name, n),
+            }
+        }
+        if unique != name {
+            self.report.push(format!("{}：消息名 {} 已被占用，包装消息改名为 {}", ctx, name, unique));
+        }
+        self.wrappers.insert(unique.clone(), field);
+        unique
+    }
+
+    /// map 的 key 只能是整数、bool 或 string
+    fn map_key(&mut self, key: &TypeExpr, ctx: &str) -> String {
+        let key = self.resolve_expr(key);
+        match &key {
+            TypeExpr::Base(base) if matches!(base.as_str(), "bool" | "string" | "byte" | "i8" | "i16" | "i32" | "i64") => {
+                self.scalar(base, ctx)
+            }
+            TypeExpr::Named(name) if self.enums.contains(name) => {
+                self.report.push(format!("{}：proto 的 map key 不能为枚举 {}，转换为 int32", ctx, name));
+                "int32".to_string()
+            }
+            other => {
+                self.report.push(format!("{}：proto 的 map key 不能为 {}，转换为 string", ctx, type_string(other)));
+                "string".to_string()
+            }
+        }
+    }
+
+    fn scalar(&mut self, base: &str, ctx: &str) -> String {
+        match base {
+            "bool" => "bool",
+            "byte" | "i8" | "i16" => {
+                self.report.push(format!("{}：proto 没有 {} 类型，转换为 int32", ctx, base));
+                "int32"
+            }
+            "i32" => "int32",
+            "i64" => "int64",
+            "double" => "double",
+            "binary" => "bytes",
+            "uuid" => {
+                self.report.push(format!("{}：proto 没有 uuid 类型，转换为 string", ctx));
+                "string"
+            }
+            "void" => {
+                self.use_empty = true;
+                "google.protobuf.Empty"
+            }
+            _ => "string",
+        }
+        .to_string()
+    }
+
+    /// 被 include 文件中的类型 `base.Base` 转换为 `{base 的 package}.Base`
+    fn message_name(&self, name: &str) -> String {
+        match name.split_once('.') {
+            Some((stem, local)) => match self.imports.iter().find(|i| i.stem == stem) {
+                Some(import) if !import.package.is_empty() => format!("{}.{}", import.package, local),
+                _ => local.to_string(),
+            },
+            None => name.to_string(),
+        }
+    }
+
+    fn service_block(&mut self, service: &Service) -> String {
+        let mut lines = comment_lines(&service.comment, "");
+        lines.push(format!("service {} {{", service.name));
+        for method in &service.methods {
+            let ctx = format!("{}.{}", service.name, method.name);
+            let request = if method.arguments.len() > 1 {
+                self.arguments_message(method, &ctx)
+            } else {
+                self.rpc_type(&method.request.name, &format!("{}Request", method.name), &ctx)
+            };
+            let response = self.rpc_type(&method.response.name, &format!("{}Response", method.name), &ctx);
+            if !method.throws.is_empty() {
+                let names: Vec<&str> = method.throws.iter().map(|e| e.r#type.as_str()).collect();
+                self.report.push(format!("{}：gRPC 通过状态码返回错误，throws ({}) 未转换", ctx, names.join(", ")));
+            }
+            self.report_method_annotations(method, &ctx);
+            lines.extend(comment_lines(&method.comment, INDENT));
+            lines.push(format!("{}rpc {}({}) returns ({});", INDENT, method.name, request, response));
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    /// rpc 只能有一个请求消息，多个参数合并为包装消息 `{方法名}Request`，每个参数对应一个字段，沿用参数编号
+    fn arguments_message(&mut self, method: &Method, ctx: &str) -> String {
+        let fields: Vec<String> = method
+            .arguments
+            .iter()
+            .map(|a| {
+                // 未标注 optional 的参数解析为 required，但 Thrift 参数本身没有 required 语义，不需要报告
+                let attribute = if a.attribute == "Optional" { "Optional" } else { "Default" };
+                let argument = Field { attribute: attribute.to_string(), ..a.clone() };
+                format!("{};", self.field(&argument, &format!("{}.{}", ctx, a.name)))
+            })
+            .collect();
+        self.wrapper_name(&format!("{}Request", method.name), fields.join(&format!("\n{}", INDENT)), ctx)
+    }
+
+    /// rpc 的请求和返回必须是消息类型，其它类型包装为 `{方法名}Request` / `{方法名}Response`
+    fn rpc_type(&mut self, ty: &str, wrapper: &str, ctx: &str) -> String {
+        if ty.is_empty() || ty == "void" {
+            self.use_empty = true;
+            return "google.protobuf.Empty".to_string();
+        }
+        let expr = self.resolve(ty);
+        if let TypeExpr::Named(name) = &expr
+            && !self.enums.contains(name)
+        {
+            return self.message_name(name);
+        }
+        let inner = match &expr {
+            TypeExpr::List(value) | TypeExpr::Set(value) => format!("repeated {}", self.single_type(value, ctx)),
+            TypeExpr::Map(key, value) => format!("map<{}, {}>", self.map_key(key, ctx), self.single_type(value, ctx)),
+            other => self.single_type(other, ctx),
+        };
+        let wrapper = self.wrapper_name(wrapper, format!("{} value = 1;", inner), ctx);
+        self.report.push(format!("{}：rpc 的参数和返回值必须是消息，{} 包装为 {}", ctx, ty, wrapper));
+        wrapper
+    }
+
+    fn report_method_annotations(&mut self, method: &Method, ctx: &str) {
+        self.report_annotations(ctx, method.annotations.iter().map(|a| &a.key));
+    }
+
+    fn report_annotations<'k>(&mut self, ctx: &str, keys: impl Iterator<Item = &'k String>) {
+        let mut keys: Vec<&str> = keys.map(|k| k.as_str()).collect();
+        keys.dedup();
+        if !keys.is_empty() {
+            self.report.push(format!("{}：注解 {} 未转换", ctx, keys.join(", ")));
+        }
+    }
+
+    /// 展开 typedef 后的类型
+    fn resolve(&self, ty: &str) -> TypeExpr {
+        self.resolve_expr(&parse_type(ty))
+    }
+
+    fn resolve_expr(&self, expr: &TypeExpr) -> TypeExpr {
+        let mut expr = expr.clone();
+        // typedef 可以嵌套，最多展开有限次避免循环定义
+        for _ in 0..16 {
+            match &expr {
+                TypeExpr::Named(name) if self.typedefs.contains_key(name) => expr = parse_type(&self.typedefs[name]),
+                _ => break,
+            }
+        }
+        expr
+    }
+}
+
+fn comment_lines(comment: &str, indent: &str) -> Vec<String> {
+    comment.lines().map(|l| format!("{}// {}", indent, l)).collect()
+}
+
+/// 包装消息名称中的组成部分，例如 `int64` 为 `Int64`，`base.Base` 为 `BaseBase`
+fn wrapper_part(ty: &str) -> String {
+    ty.split('.')
+        .map(|seg| {
+            let mut chars = seg.chars();
+            chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        })
+        .collect()
+}
+
+/// `ItemStatus` 转换为 `ITEM_STATUS`
+fn upper_snake(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 && !result.ends_with('_') {
+            result.push('_');
+        }
+        result.push(c.to_ascii_uppercase());
+    }
+    result
+}
+
+fn type_string(expr: &TypeExpr) -> String {
+    match expr {
+        TypeExpr::Base(name) | TypeExpr::Named(name) => name.clone(),
+        TypeExpr::List(value) => format!("list<{}>", type_string(value)),
+        TypeExpr::Set(value) => format!("set<{}>", type_string(value)),
+        TypeExpr::Map(key, value) => format!("map<{}, {}>", type_string(key), type_string(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl_parser::{build_document, parse_idl};
+
+    const SAMPLE_THRIFT: &str = r#"
+        namespace rs volo.example
+
+        typedef i64 ItemId
+
+        enum Status { ON = 1, OFF = 2 }
+
+        union Key { 1: ItemId id, 2: list<string> slugs }
+
+        struct Item {
+            1: required ItemId id,
+            2: optional string title,
+            3: set<i16> tags,
+            4: map<string, list<Status>> groups,
+        }
+
+        exception NotFound { 1: string message }
+
+        service ItemService {
+            Item GetItem (1: Key key) throws (1: NotFound e),
+            void Ping (1: i64 id),
+        }
+    "#;
+
+    #[test]
+    fn test_thrift_to_proto() {
+        let doc = build_document(&parse_idl(SAMPLE_THRIFT).unwrap());
+        let output = thrift_to_proto(&doc, "item.thrift", &[]);
+        let proto = &output.proto;
+
+        assert!(proto.contains("syntax = \"proto3\";\n\npackage volo.example;"));
+        assert!(proto.contains("import \"google/protobuf/empty.proto\";"));
+        assert!(proto.contains("enum Status {\n  STATUS_UNSPECIFIED = 0;\n  ON = 1;\n  OFF = 2;\n}"));
+        assert!(proto.contains("message Key {\n  oneof value {\n    int64 id = 1;\n    StringList slugs = 2;\n  }\n}"));
+        assert!(proto.contains("  int64 id = 1;\n  optional string title = 2;\n  repeated int32 tags = 3;\n  map<string, StatusList> groups = 4;"));
+        assert!(proto.contains("message StringList {\n  repeated string values = 1;\n}"));
+        assert!(proto.contains("message PingRequest {\n  int64 value = 1;\n}"));
+        assert!(proto.contains("  rpc GetItem(Key) returns (Item);\n  rpc Ping(PingRequest) returns (google.protobuf.Empty);"));
+
+        let report = output.report.join("\n");
+        assert!(report.contains("Item.id：proto3 不支持 required"));
+        assert!(report.contains("Item.tags：set 转换为 repeated"));
+        assert!(report.contains("ItemService.GetItem：gRPC 通过状态码返回错误"));
+        assert!(report.contains("typedef ItemId"));
+    }
+
+    #[test]
+    fn test_proto_name_conflicts() {
+        let doc = build_document(&parse_idl(r#"
+            enum Status { ON = 0, OFF = 1 }
+            enum Switch { ON = 0, AUTO = 1 }
+
+            struct PingRequest { 1: string token }
+            struct StringList { 1: string name }
+
+            service ItemService {
+                void Ping (1: i64 id, 2: string token, 3: bool force),
+                list<string> Tags (1: PingRequest req),
+            }
+
+            service ShopService {
+                void Ping (1: i32 id),
+                list<string> Names (1: PingRequest req),
+                map<string, list<string>> Groups (1: PingRequest req),
+            }
+        "#).unwrap());
+        let output = thrift_to_proto(&doc, "item.thrift", &[]);
+        let proto = &output.proto;
+
+        assert!(proto.contains("message PingRequest {\n  string token = 1;\n}"));
+        // 多个参数合并为一个请求消息，沿用参数编号
+        assert!(proto.contains("message PingRequest2 {\n  int64 id = 1;\n  string token = 2;\n  bool force = 3;\n}"));
+        assert!(proto.contains("message PingRequest3 {\n  int32 value = 1;\n}"));
+        assert!(proto.contains("  rpc Ping(PingRequest2) returns (google.protobuf.Empty);"));
+        assert!(proto.contains("  rpc Ping(PingRequest3) returns (google.protobuf.Empty);"));
+        // 字段相同的包装消息共用
+        assert!(proto.contains("message TagsResponse {\n  repeated string value = 1;\n}"));
+        assert!(proto.contains("message NamesResponse {\n  repeated string value = 1;\n}"));
+        assert!(proto.contains("message StringList {\n  string name = 1;\n}"));
+        assert!(proto.contains("message StringList2 {\n  repeated string values = 1;\n}"));
+        assert!(proto.contains("message GroupsResponse {\n  map<string, StringList2> value = 1;\n}"));
+
+        let report = output.report.join("\n");
+        assert!(report.contains("ItemService.Ping：消息名 PingRequest 已被占用，包装消息改名为 PingRequest2"));
+        assert!(!report.contains("rpc 只能有一个参数"));
+        assert!(!report.contains("ItemService.Ping.id"));
+        assert!(report.contains("enum Switch.ON：与 enum Status 中的值同名"));
+        assert!(!report.contains("Switch.AUTO"));
+    }
+}
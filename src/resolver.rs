@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::idl_parser::{self, ConstValue, Document};
+use crate::source_map;
+
+/// 加载 IDL 文件及其（递归）include 的文件，合并为一个 Document
+///
+/// 被 include 文件中的结构体、枚举、typedef 和常量以 `文件名.类型名` 命名，
+/// 与 Thrift 中引用它们的写法一致，因此合并后所有类型引用都能在 Document 中找到定义。
+/// 服务只保留入口文件中定义的服务。
+pub fn load_resolved_document(idl_path: &str) -> Result<Document, String> {
+    let mut visited = HashSet::new();
+    visited.insert(normalize(Path::new(idl_path)));
+    let (mut doc, includes) = load_document(Path::new(idl_path))?;
+
+    let dir = Path::new(idl_path).parent().unwrap_or(Path::new("")).to_path_buf();
+    let mut pending: Vec<PathBuf> = includes.into_iter().map(|inc| dir.join(inc)).collect();
+    while let Some(path) = pending.pop() {
+        if !visited.insert(normalize(&path)) {
+            continue;
+        }
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let (mut included, includes) = load_document(&path)?;
+        qualify(&mut included, &stem);
+        doc.structs.extend(included.structs);
+        doc.enums.extend(included.enums);
+        doc.typedefs.extend(included.typedefs);
+        doc.constants.extend(included.constants);
+
+        let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        pending.extend(includes.into_iter().map(|inc| dir.join(inc)));
+    }
+    Ok(doc)
+}
+
+/// 读取并解析单个文件（不展开 include），返回整理后带有注释和行号的 Document 及其 include 列表
+pub fn load_document(path: &Path) -> Result<(Document, Vec<String>), String> {
+    let content = fs::read_to_string(path)
+        .map_err(|_| format!("读取 IDL 文件失败: {}", path.display()))?;
+    let file = idl_parser::parse_idl(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut doc = idl_parser::build_document(&file);
+    source_map::apply_source_info(&mut doc, &content);
+    Ok((doc, idl_parser::collect_includes(&file)))
+}
+
+/// 给被 include 文件中定义的类型及其内部的类型引用加上 `文件名.` 前缀
+fn qualify(doc: &mut Document, stem: &str) {
+    let local: HashSet<String> = doc
+        .structs
+        .iter()
+        .map(|s| s.name.clone())
+        .chain(doc.enums.iter().map(|e| e.name.clone()))
+        .chain(doc.typedefs.iter().map(|t| t.name.clone()))
+        .chain(doc.constants.iter().map(|c| c.name.clone()))
+        .collect();
+    let rename = |ty: &str| qualify_type(ty, stem, &local);
+
+    for s in &mut doc.structs {
+        s.name = format!("{}.{}", stem, s.name);
+        for f in &mut s.fields {
+            f.r#type = rename(&f.r#type);
+            if let Some(default) = &mut f.default {
+                qualify_value(default, stem, &local);
+            }
+        }
+    }
+    for e in &mut doc.enums {
+        e.name = format!("{}.{}", stem, e.name);
+    }
+    for t in &mut doc.typedefs {
+        t.name = format!("{}.{}", stem, t.name);
+        t.r#type = rename(&t.r#type);
+    }
+    for c in &mut doc.constants {
+        c.name = format!("{}.{}", stem, c.name);
+        c.r#type = rename(&c.r#type);
+        qualify_value(&mut c.value, stem, &local);
+    }
+}
+
+/// 将常量值中对 `local` 中常量或枚举项（`Status.ON`）的引用替换为 `stem.名称`
+fn qualify_value(value: &mut ConstValue, stem: &str, local: &HashSet<String>) {
+    match value {
+        ConstValue::Ref(name) => {
+            let head = name.split('.').next().unwrap_or_default();
+            if local.contains(head) {
+                *name = format!("{}.{}", stem, name);
+            }
+        }
+        ConstValue::List(items) => items.iter_mut().for_each(|v| qualify_value(v, stem, local)),
+        ConstValue::Map(entries) => {
+            for (k, v) in entries {
+                qualify_value(k, stem, local);
+                qualify_value(v, stem, local);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 将类型字符串中属于 `local` 的类型名替换为 `stem.类型名`
+fn qualify_type(ty: &str, stem: &str, local: &HashSet<String>) -> String {
+    let mut result = String::new();
+    let mut token = String::new();
+    for c in ty.chars().chain(std::iter::once('\0')) {
+        if c.is_alphanumeric() || c == '_' || c == '.' {
+            token.push(c);
+            continue;
+        }
+        if local.contains(&token) {
+            result.push_str(stem);
+            result.push('.');
+        }
+        result.push_str(&token);
+        token.clear();
+        if c != '\0' {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_load_resolved_document() {
+        let dir = temp_dir().join("volodoc_resolver_test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("base.thrift"),
+            "const i32 LIMIT = 20\nstruct Base { 1: string log_id, 2: list<Extra> extras, 3: i32 limit = LIMIT }\nstruct Extra { 1: string key }",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("item.thrift"),
+            "include \"base.thrift\"\nstruct Item { 1: i64 id, 2: base.Base base }\nservice S { Item Get (1: base.Base req) }",
+        )
+        .unwrap();
+
+        let doc = load_resolved_document(dir.join("item.thrift").to_str().unwrap()).unwrap();
+        let names: Vec<&str> = doc.structs.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Item", "base.Base", "base.Extra"]);
+        assert_eq!(doc.structs[1].fields[1].r#type, "list<base.Extra>");
+        assert_eq!(doc.constants[0].name, "base.LIMIT");
+
+        // 展开 include 后，参数为 include 文件中的类型时也能生成示例
+        let samples = crate::sample_data_generator::generate_document_samples(&doc);
+        assert_eq!(samples["S"]["Get"]["request"]["log_id"], "10001");
+        assert_eq!(samples["S"]["Get"]["request"]["limit"], 20);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
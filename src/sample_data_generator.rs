@@ -1,91 +1,1490 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use pilota_thrift_parser::File;
+use regex::Regex;
+use regex_syntax::hir::{Class, Hir, HirKind};
 use serde_json::{json, Value};
 
-// 假定你已在 idl_parser 模块中定义了 Service、Struct、Field、extract_services 和 collect_structs 函数
-use crate::idl_parser::{Struct as MyStruct, extract_services, collect_structs};
-
-/// 根据字段类型生成示例值
-fn get_sample_value(field_type: &str, struct_map: &HashMap<String, MyStruct>) -> Value {
-    match field_type.to_lowercase().as_str() {
-        "i64" | "int" | "integer" => json!(123),
-        "string" => json!("example"),
-        "bool" | "boolean" => json!(true),
-        _ => {
-            if field_type.starts_with("map<") {
-                // 示例：针对 map<string, string> 返回 {"example_key": "example_value"}
-                json!({"example_key": "example_value"})
-            } else if field_type.starts_with("list<") {
-                // 示例：返回一个数组，数组内的元素由 inner 类型生成
-                let inner = field_type.trim_start_matches("list<").trim_end_matches('>');
-                json!([get_sample_value(inner, struct_map)])
-            } else if struct_map.contains_key(field_type) {
-                // 如果当前类型是已定义的结构体，递归生成示例数据
-                generate_sample_for_struct(struct_map.get(field_type).unwrap(), struct_map)
-            } else {
-                // 默认生成字符串示例
-                json!("example")
+use crate::config::{self, SampleConfig};
+use crate::idl_parser::{build_document, parse_type, ConstValue, Constant, Constraints, Document, Enum, Struct as MyStruct, TypeExpr};
+
+/// 测试用例中不限制大小的列表、map 的元素个数
+const LARGE_SIZE: usize = 100;
+/// 测试用例中不限制长度的字符串的长度
+const LONG_STRING: usize = 256;
+/// 生成的字符串长度、容器元素个数的上限，避免 `_test.md` 过于庞大：
+/// 示例值最多补足到该长度，约束要求更长的边界用例不生成
+const MAX_SIZE: usize = 1000;
+/// 随机模式下字符串示例值使用的单词
+const WORDS: [&str; 12] = [
+    "apple", "banana", "cherry", "delta", "echo", "falcon", "galaxy", "harbor", "island", "jungle", "kiwi", "lemon",
+];
+
+/// 随机模式使用的伪随机数生成器（SplitMix64）
+///
+/// 不依赖外部 crate，保证相同的种子在任何平台、任何版本下生成相同的序列，生成的文档才能稳定地提交到 git。
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// [low, high] 范围内的整数
+    fn range(&mut self, low: i64, high: i64) -> i64 {
+        low + (self.next() % (high - low + 1) as u64) as i64
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+/// 字符串的 FNV-1a 哈希，用于由种子和方法名得到每个方法各自的种子
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// 内置的按字段名生成示例值的规则，同一字段名可以有多个不同类型的值，按顺序取第一个与字段类型相符的值
+fn builtin_rules() -> Vec<(&'static str, Value)> {
+    vec![
+        ("id", json!(10001)),
+        ("*_id", json!(10001)),
+        ("id", json!("10001")),
+        ("*_id", json!("10001")),
+        ("email", json!("user@example.com")),
+        ("*_email", json!("user@example.com")),
+        ("phone", json!("+8613800138000")),
+        ("mobile", json!("+8613800138000")),
+        ("*_phone", json!("+8613800138000")),
+        ("url", json!("https://example.com")),
+        ("*_url", json!("https://example.com")),
+        ("ip", json!("192.168.1.1")),
+        ("*_ip", json!("192.168.1.1")),
+        // 时间：整数为秒级时间戳，字符串为 RFC 3339 格式
+        ("*_at", json!(1700000000)),
+        ("*_time", json!(1700000000)),
+        ("*_at", json!("2023-11-14T22:13:20Z")),
+        ("*_time", json!("2023-11-14T22:13:20Z")),
+        ("name", json!("张三")),
+        ("*_name", json!("张三")),
+        // 价格：double 为元，整数为分
+        ("price", json!(99.9)),
+        ("*_price", json!(99.9)),
+        ("amount", json!(99.9)),
+        ("price", json!(9990)),
+        ("*_price", json!(9990)),
+        ("amount", json!(9990)),
+        ("country", json!("CN")),
+        ("country_code", json!("CN")),
+    ]
+}
+
+/// 生成示例数据时需要查找的类型定义
+struct Sampler<'a> {
+    structs: HashMap<&'a str, &'a MyStruct>,
+    enums: HashMap<&'a str, &'a Enum>,
+    typedefs: HashMap<&'a str, &'a str>,
+    constants: HashMap<&'a str, &'a Constant>,
+    config: &'a SampleConfig,
+    /// 当前使用的可选字段模式：omit、null 或 fill（both 模式分别以 fill 和 omit 各生成一次）
+    optional: &'a str,
+    /// 按字段名生成示例值的规则：配置中的规则在前，内置规则在后
+    rules: Vec<(String, Value)>,
+    /// 正在展开的结构体，用于发现递归引用
+    path: Vec<&'a str>,
+    /// 正在展开的常量，用于发现常量之间的循环引用
+    const_path: Vec<&'a str>,
+    /// 因递归层数达到 max_depth 而截断的结构体
+    truncated: BTreeSet<String>,
+    /// 随机模式（配置了 seed）下的随机数生成器，每个方法开始生成前重新设置
+    rng: Option<Rng>,
+}
+
+impl<'a> Sampler<'a> {
+    fn new(doc: &'a Document, config: &'a SampleConfig) -> Self {
+        Sampler {
+            structs: doc.structs.iter().map(|s| (s.name.as_str(), s)).collect(),
+            enums: doc.enums.iter().map(|e| (e.name.as_str(), e)).collect(),
+            typedefs: doc.typedefs.iter().map(|t| (t.name.as_str(), t.r#type.as_str())).collect(),
+            constants: doc.constants.iter().map(|c| (c.name.as_str(), c)).collect(),
+            config,
+            optional: &config.optional,
+            rules: config
+                .rules
+                .iter()
+                .map(|r| (r.field.clone(), r.value.clone()))
+                .chain(builtin_rules().into_iter().map(|(field, value)| (field.to_string(), value)))
+                .collect(),
+            path: Vec::new(),
+            const_path: Vec::new(),
+            truncated: BTreeSet::new(),
+            rng: None,
+        }
+    }
+
+    /// 根据字段类型生成示例值
+    fn get_sample_value(&mut self, field_type: &str) -> Value {
+        self.sample_value(&parse_type(field_type), 0)
+    }
+
+    /// 类型的第 n 个（从 0 开始）示例值，基础类型和枚举在不同的 n 下得到不同的值，用于生成元素互不相同的 set
+    fn sample_value(&mut self, ty: &TypeExpr, n: usize) -> Value {
+        match ty {
+            TypeExpr::Base(base) => self.base_value(base, n),
+            TypeExpr::Map(key, value) => {
+                let mut map = serde_json::Map::new();
+                if self.truncates(value) {
+                    return Value::Object(map);
+                }
+                let len = self.container_len();
+                for i in self.distinct_attempts(len) {
+                    if map.len() == len {
+                        break;
+                    }
+                    // JSON 对象的 key 只能是字符串，非字符串类型的 key 转换为字符串，例如 map<i32, Item> 的 key 为 "123456"
+                    let key = match self.sample_value(key, i) {
+                        Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    if !map.contains_key(&key) {
+                        let value = self.sample_value(value, i);
+                        map.insert(key, value);
+                    }
+                }
+                Value::Object(map)
+            }
+            // 元素类型需要截断时生成空容器
+            TypeExpr::List(inner) | TypeExpr::Set(inner) if self.truncates(inner) => json!([]),
+            // 示例：返回一个数组，数组内的元素由 inner 类型生成
+            TypeExpr::List(inner) => {
+                let len = self.container_len();
+                Value::Array((0..len).map(|i| self.sample_value(inner, i)).collect())
+            }
+            TypeExpr::Set(inner) => {
+                // set 的元素互不相同，无法生成不同值的类型（如固定模式下的结构体）只保留一个元素
+                let len = self.container_len();
+                let mut values = Vec::new();
+                for i in self.distinct_attempts(len) {
+                    if values.len() == len {
+                        break;
+                    }
+                    let value = self.sample_value(inner, i);
+                    if !values.contains(&value) {
+                        values.push(value);
+                    }
+                }
+                Value::Array(values)
+            }
+            TypeExpr::Named(name) => {
+                if let Some(&s) = self.structs.get(name.as_str()) {
+                    // 如果当前类型是已定义的结构体，递归生成示例数据，递归层数达到上限时为 null
+                    if self.truncates(ty) { Value::Null } else { self.generate_sample_for_struct(s) }
+                } else if let Some(e) = self.enums.get(name.as_str()) {
+                    // Thrift JSON 协议中枚举以整数值表示
+                    let n = match &mut self.rng {
+                        Some(rng) => rng.next() as usize,
+                        None => n,
+                    };
+                    e.values.get(n % e.values.len().max(1)).map(|v| json!(v.value)).unwrap_or(Value::Null)
+                } else if let Some(target) = self.typedefs.get(name.as_str()) {
+                    self.sample_value(&parse_type(target), n)
+                } else {
+                    // include 文件中的类型在单个文件中无法解析
+                    Value::Null
+                }
             }
         }
     }
-}
 
-/// 根据结构体定义生成示例 JSON 对象（递归支持嵌套结构体）
-/// 可选字段（optional）在测试中生成 null。
-fn generate_sample_for_struct(s: &MyStruct, struct_map: &HashMap<String, MyStruct>) -> Value {
-    let mut map = serde_json::Map::new();
-    for field in &s.fields {
-        if field.attribute.to_lowercase().contains("optional") {
-            // 对于可选类型直接输出 null，不生成示例数据
-            map.insert(field.name.clone(), json!(null));
-        } else {
-            // 必填字段生成实际示例数据
-            let sample = get_sample_value(&field.r#type, struct_map);
+    /// 类型是否为递归层数已达到 `max_depth` 的结构体（同一结构体在当前展开路径中出现的次数），是则记录下来
+    fn truncates(&mut self, ty: &TypeExpr) -> bool {
+        let TypeExpr::Named(name) = ty else {
+            return false;
+        };
+        if let Some(target) = self.typedefs.get(name.as_str()) {
+            return self.truncates(&parse_type(target));
+        }
+        if !self.structs.contains_key(name.as_str()) {
+            return false;
+        }
+        let depth = self.path.iter().filter(|n| **n == name.as_str()).count();
+        if depth < self.config.max_depth {
+            return false;
+        }
+        self.truncated.insert(name.clone());
+        true
+    }
+
+    /// 列表、set、map 的元素个数：固定模式为 list_min，随机模式在 [list_min, list_max] 中随机选取
+    fn container_len(&mut self) -> usize {
+        let (min, max) = (self.config.list_min, self.config.list_max);
+        match &mut self.rng {
+            Some(rng) => rng.range(min as i64, max as i64) as usize,
+            None => min,
+        }
+    }
+
+    /// 生成 len 个互不相同的元素时的尝试次数，随机模式可能生成重复的值，多尝试几次
+    fn distinct_attempts(&self, len: usize) -> std::ops::Range<usize> {
+        if self.rng.is_some() { 0..len * 3 } else { 0..len }
+    }
+
+    /// 基础类型的第 n 个示例值，整数取值均在对应类型的范围内；随机模式下忽略 n，生成随机值
+    fn base_value(&mut self, base: &str, n: usize) -> Value {
+        if let Some(rng) = &mut self.rng {
+            return random_base_value(rng, base, self.config.i64 == "string");
+        }
+        let offset = n as i64;
+        match base {
+            "bool" => json!(n.is_multiple_of(2)),
+            "byte" | "i8" => json!(12 + offset % 100),
+            "i16" => json!(1234 + offset),
+            "i32" => json!(123456 + offset),
+            // i64 超出 JavaScript 安全整数范围时会丢失精度，部分服务约定以字符串传输
+            "i64" if self.config.i64 == "string" => json!((1234567890123 + offset).to_string()),
+            "i64" => json!(1234567890123 + offset),
+            "double" => json!(12.5 + offset as f64),
+            "binary" => json!(encode_base64(sample_string(n).as_bytes())),
+            "uuid" => json!(format!("123e4567-e89b-12d3-a456-{:012x}", 0x426614174000 + n)),
+            "void" => Value::Null,
+            _ => json!(sample_string(n)),
+        }
+    }
+
+    /// 根据结构体定义生成示例 JSON 对象（递归支持嵌套结构体）
+    /// 可选字段（optional）按 `optional` 模式省略、生成 null 或生成实际示例数据。
+    /// 字段有默认值时优先使用默认值（null 模式下的可选字段也使用默认值），其次使用字段名规则，最后按类型生成。
+    fn generate_sample_for_struct(&mut self, s: &'a MyStruct) -> Value {
+        self.path.push(&s.name);
+        let mut map = serde_json::Map::new();
+        for field in &s.fields {
+            let optional = field.attribute == "Optional";
+            if optional && self.optional == "omit" {
+                continue;
+            }
+            let ty = parse_type(&field.r#type);
+            let default = field.default.as_ref().and_then(|d| self.const_value(d, &ty));
+            let sample = match default {
+                Some(value) => value,
+                None if optional && self.optional == "null" => Value::Null,
+                None => match self.rule_value(&field.name, &field.r#type) {
+                    Some(value) => value,
+                    None => self.sample_value(&ty, 0),
+                },
+            };
+            let sample = self.constrain(sample, &field.constraints, &ty);
             map.insert(field.name.clone(), sample);
         }
+        self.path.pop();
+        Value::Object(map)
+    }
+
+    /// 将常量值（默认值或 const 定义）转换为 ty 类型的示例值，
+    /// 引用的常量或枚举项不存在、常量循环引用、与类型不符时返回 None
+    fn const_value(&mut self, value: &ConstValue, ty: &TypeExpr) -> Option<Value> {
+        if let TypeExpr::Named(name) = ty
+            && let Some(target) = self.typedefs.get(name.as_str())
+        {
+            return self.const_value(value, &parse_type(target));
+        }
+        match (value, ty) {
+            (ConstValue::Ref(name), _) => {
+                if let Some(&c) = self.constants.get(name.as_str()) {
+                    if self.const_path.contains(&c.name.as_str()) {
+                        return None;
+                    }
+                    self.const_path.push(&c.name);
+                    let value = self.const_value(&c.value, ty);
+                    self.const_path.pop();
+                    return value;
+                }
+                // 枚举项：`Status.ON`
+                let (enum_name, value_name) = name.rsplit_once('.')?;
+                let v = self.enums.get(enum_name)?.values.iter().find(|v| v.name == value_name)?;
+                Some(json!(v.value))
+            }
+            (ConstValue::Int(n), TypeExpr::Base(base)) => Some(match base.as_str() {
+                "bool" => json!(*n != 0),
+                "double" => json!(*n as f64),
+                "i64" if self.config.i64 == "string" => json!(n.to_string()),
+                _ => json!(n),
+            }),
+            (ConstValue::String(s), TypeExpr::Base(base)) if base == "binary" => Some(json!(encode_base64(s.as_bytes()))),
+            (ConstValue::Bool(b), _) => Some(json!(b)),
+            (ConstValue::Int(n), _) => Some(json!(n)),
+            (ConstValue::Double(d), _) => Some(json!(d)),
+            (ConstValue::String(s), _) => Some(json!(s)),
+            (ConstValue::List(items), TypeExpr::List(inner) | TypeExpr::Set(inner)) => {
+                items.iter().map(|item| self.const_value(item, inner)).collect::<Option<Vec<_>>>().map(Value::Array)
+            }
+            (ConstValue::Map(entries), TypeExpr::Map(key_type, value_type)) => {
+                let mut map = serde_json::Map::new();
+                for (k, v) in entries {
+                    let key = match self.const_value(k, key_type)? {
+                        Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    let value = self.const_value(v, value_type)?;
+                    map.insert(key, value);
+                }
+                Some(Value::Object(map))
+            }
+            (ConstValue::Map(entries), TypeExpr::Named(name)) => {
+                // 结构体字面量：在生成的示例上覆盖字面量中给出的字段
+                let &s = self.structs.get(name.as_str())?;
+                if self.truncates(ty) {
+                    return None;
+                }
+                let mut sample = self.generate_sample_for_struct(s);
+                for (k, v) in entries {
+                    let ConstValue::String(field_name) = k else {
+                        return None;
+                    };
+                    let field = s.fields.iter().find(|f| &f.name == field_name)?;
+                    let value = self.const_value(v, &parse_type(&field.r#type))?;
+                    sample[field_name.as_str()] = value;
+                }
+                Some(sample)
+            }
+            _ => None,
+        }
+    }
+
+    /// 按字段名匹配规则，返回第一条值与字段类型相符的规则的值；只用于基础类型（包括 typedef 的基础类型）的字段
+    fn rule_value(&self, field_name: &str, field_type: &str) -> Option<Value> {
+        let TypeExpr::Base(base) = self.resolve(&parse_type(field_type)) else {
+            return None;
+        };
+        let name = snake_case(field_name);
+        self.rules
+            .iter()
+            .filter(|(pattern, _)| matches_pattern(&pattern.to_lowercase(), &name))
+            .find_map(|(_, value)| self.convert_rule_value(&base, value))
+    }
+
+    /// 展开 typedef 得到实际类型
+    fn resolve(&self, ty: &TypeExpr) -> TypeExpr {
+        match ty {
+            TypeExpr::Named(name) if self.typedefs.contains_key(name.as_str()) => {
+                self.resolve(&parse_type(self.typedefs[name.as_str()]))
+            }
+            _ => ty.clone(),
+        }
+    }
+
+    /// 调整示例值使其满足字段的约束：不在 vt.in 中时取第一个允许的值，数值超出范围时取范围内的值，
+    /// 字符串不匹配 vt.pattern 时按正则表达式生成，长度不符时补齐或截断，列表、set、map 补足或截断元素（最多补足到 `MAX_SIZE`）
+    fn constrain(&mut self, value: Value, c: &Constraints, ty: &TypeExpr) -> Value {
+        if value.is_null() {
+            return value;
+        }
+        let ty = self.resolve(ty);
+        if !c.r#in.is_empty() {
+            let allowed: Vec<Value> = c.r#in.iter().filter_map(|raw| self.allowed_value(raw, &ty)).collect();
+            return if allowed.is_empty() || allowed.contains(&value) { value } else { allowed[0].clone() };
+        }
+        match (value, &ty) {
+            (Value::Number(n), TypeExpr::Base(base)) => match base.as_str() {
+                "double" => json!(float_in_range(n.as_f64().unwrap_or_default(), c)),
+                _ => json!(int_in_range(n.as_i64().unwrap_or_default(), base, c)),
+            },
+            // 以字符串表示的 i64
+            (Value::String(s), TypeExpr::Base(base)) if base == "i64" => {
+                json!(int_in_range(s.parse().unwrap_or_default(), base, c).to_string())
+            }
+            (Value::String(s), _) => {
+                if let Some(pattern) = &c.pattern
+                    && !Regex::new(pattern).is_ok_and(|re| re.is_match(&s))
+                    && let Some(generated) = pattern_sample(pattern)
+                {
+                    return json!(generated);
+                }
+                let mut s: String = s.chars().take(c.max_size.unwrap_or(usize::MAX)).collect();
+                while s.chars().count() < c.min_size.unwrap_or(0).min(MAX_SIZE) {
+                    s.push('x');
+                }
+                json!(s)
+            }
+            (Value::Array(mut items), TypeExpr::List(inner) | TypeExpr::Set(inner)) => {
+                items.truncate(c.max_size.unwrap_or(usize::MAX));
+                let min = c.min_size.unwrap_or(0).min(MAX_SIZE);
+                // set 需要互不相同的元素，尝试若干次仍不够时保留已有的元素
+                for n in items.len()..min * 3 {
+                    if items.len() >= min {
+                        break;
+                    }
+                    let item = self.sample_value(inner, n);
+                    if matches!(ty, TypeExpr::List(_)) || !items.contains(&item) {
+                        items.push(item);
+                    }
+                }
+                Value::Array(items)
+            }
+            (Value::Object(mut map), TypeExpr::Map(key_type, value_type)) => {
+                while map.len() > c.max_size.unwrap_or(usize::MAX) {
+                    let last = map.keys().next_back().cloned().unwrap_or_default();
+                    map.remove(&last);
+                }
+                let min = c.min_size.unwrap_or(0).min(MAX_SIZE);
+                for n in map.len()..min * 3 {
+                    if map.len() >= min {
+                        break;
+                    }
+                    let key = match self.sample_value(key_type, n) {
+                        Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    if !map.contains_key(&key) {
+                        let value = self.sample_value(value_type, n);
+                        map.insert(key, value);
+                    }
+                }
+                Value::Object(map)
+            }
+            (value, _) => value,
+        }
+    }
+
+    /// vt.in 中的一个取值转换为示例值：字符串去掉引号，枚举类型可以写枚举项名称
+    fn allowed_value(&self, raw: &str, ty: &TypeExpr) -> Option<Value> {
+        let unquoted = raw.trim_matches(|c| c == '"' || c == '\'');
+        match ty {
+            TypeExpr::Base(base) if base == "string" || base == "binary" => Some(json!(unquoted)),
+            TypeExpr::Base(base) if base == "double" => raw.parse::<f64>().ok().map(|n| json!(n)),
+            TypeExpr::Base(base) if base == "i64" && self.config.i64 == "string" => {
+                raw.parse::<i64>().ok().map(|n| json!(n.to_string()))
+            }
+            TypeExpr::Base(base) if base == "bool" => raw.parse::<bool>().ok().map(|b| json!(b)),
+            TypeExpr::Named(name) if self.enums.contains_key(name.as_str()) => {
+                let value_name = unquoted.rsplit('.').next().unwrap_or_default();
+                match self.enums[name.as_str()].values.iter().find(|v| v.name == value_name) {
+                    Some(v) => Some(json!(v.value)),
+                    None => raw.parse::<i64>().ok().map(|n| json!(n)),
+                }
+            }
+            _ => raw.parse::<i64>().ok().map(|n| json!(n)),
+        }
+    }
+
+    /// 规则中的值作为该基础类型的示例值，类型不符或整数超出范围时返回 None
+    fn convert_rule_value(&self, base: &str, value: &Value) -> Option<Value> {
+        let in_range = |n: i64| match base {
+            "byte" | "i8" => i8::try_from(n).is_ok(),
+            "i16" => i16::try_from(n).is_ok(),
+            "i32" => i32::try_from(n).is_ok(),
+            _ => true,
+        };
+        match (base, value) {
+            ("string", Value::String(_)) | ("bool", Value::Bool(_)) | ("double", Value::Number(_)) => Some(value.clone()),
+            ("i64", Value::Number(n)) if self.config.i64 == "string" => n.as_i64().map(|n| json!(n.to_string())),
+            ("byte" | "i8" | "i16" | "i32" | "i64", Value::Number(n)) => n.as_i64().filter(|n| in_range(*n)).map(|n| json!(n)),
+            _ => None,
+        }
+    }
+
+    /// 方法的测试用例：在正常请求 `base` 的基础上逐个字段替换为边界值、删除必填字段、替换为类型错误的值，
+    /// 参数不是本文件中的结构体时对整个参数生成边界值和类型错误的用例，没有参数时没有用例
+    fn test_cases(&mut self, request: &MyStruct, base: &Value) -> Vec<Value> {
+        if request.name.is_empty() {
+            return Vec::new();
+        }
+        let Some(&s) = self.structs.get(request.name.as_str()) else {
+            let ty = parse_type(&request.name);
+            let mut cases: Vec<Value> = self
+                .boundary_values(&ty, &Constraints::default())
+                .into_iter()
+                .map(|(label, accept, value)| test_case(label, "boundary", accept, value))
+                .collect();
+            cases.extend(self.wrong_type_value(&ty).map(|v| test_case("类型错误".to_string(), "wrong_type", false, v)));
+            return cases;
+        };
+        let Value::Object(base) = base else {
+            return Vec::new();
+        };
+        let with = |name: &str, value: Value| {
+            let mut request = base.clone();
+            request.insert(name.to_string(), value);
+            Value::Object(request)
+        };
+
+        let mut cases = Vec::new();
+        for field in &s.fields {
+            for (label, accept, value) in self.boundary_values(&parse_type(&field.r#type), &field.constraints) {
+                cases.push(test_case(format!("{}：{}", field.name, label), "boundary", accept, with(&field.name, value)));
+            }
+        }
+        let optional = |name: &str| s.fields.iter().any(|f| f.name == name && f.attribute == "Optional");
+        if base.keys().any(|k| optional(k)) {
+            let request = base.iter().filter(|(k, _)| !optional(k)).map(|(k, v)| (k.clone(), v.clone())).collect();
+            cases.push(test_case("省略所有可选字段".to_string(), "boundary", true, Value::Object(request)));
+        }
+        for field in s.fields.iter().filter(|f| f.attribute == "Required") {
+            let mut request = base.clone();
+            request.remove(&field.name);
+            cases.push(test_case(format!("缺少必填字段 {}", field.name), "missing_required", false, Value::Object(request)));
+        }
+        for field in &s.fields {
+            if let Some(value) = self.wrong_type_value(&parse_type(&field.r#type)) {
+                cases.push(test_case(format!("{}：类型错误", field.name), "wrong_type", false, with(&field.name, value)));
+            }
+        }
+        cases
+    }
+
+    /// 类型和约束的边界值，返回 (说明, 预期是否接受, 值)：
+    /// 整数取类型或约束的上下限及超出一位的值，字符串取空字符串、最小 / 最大长度、长字符串或是否匹配 pattern，
+    /// 列表、set、map 取空容器、最小 / 最大元素个数或大容器，枚举取不存在的值，有 vt.in 时取可选值和可选值以外的值
+    fn boundary_values(&mut self, ty: &TypeExpr, c: &Constraints) -> Vec<(String, bool, Value)> {
+        let ty = self.resolve(ty);
+        let mut cases = Vec::new();
+        if !c.r#in.is_empty() {
+            let allowed: Vec<Value> = c.r#in.iter().filter_map(|raw| self.allowed_value(raw, &ty)).collect();
+            if let Some(first) = allowed.first() {
+                cases.push((format!("取可选值 {}", first), true, first.clone()));
+            }
+            if let Some(other) = self.disallowed_value(&allowed, &ty) {
+                cases.push((format!("取可选值以外的值 {}", other), false, other));
+            }
+            return cases;
+        }
+        match &ty {
+            TypeExpr::Base(base) if matches!(base.as_str(), "byte" | "i8" | "i16" | "i32" | "i64") => {
+                let (type_min, type_max) = int_type_range(base);
+                // 约束先与类型范围取交集，超出类型范围的约束边界不会作为可接受的值
+                let Some((low, high)) = int_range(base, c) else {
+                    // 没有满足约束的整数，类型范围内的任何值都应被拒绝
+                    let value = if int_bounds(c).0.is_some_and(|low| low > type_max as i128) { type_max } else { type_min };
+                    cases.push((format!("约束与类型范围无交集 {}", value), false, self.int_value(base, value)));
+                    return cases;
+                };
+                if low > type_min {
+                    cases.push((format!("取约束下限 {}", low), true, self.int_value(base, low)));
+                    cases.push((format!("低于约束下限 {}", low - 1), false, self.int_value(base, low - 1)));
+                } else {
+                    cases.push((format!("取最小值 {}", type_min), true, self.int_value(base, type_min)));
+                    // i64 的上下限以外无法用 i64 表示
+                    if base != "i64" {
+                        cases.push((format!("超出类型范围 {}", type_min - 1), false, json!(type_min - 1)));
+                    }
+                }
+                if high < type_max {
+                    cases.push((format!("取约束上限 {}", high), true, self.int_value(base, high)));
+                    cases.push((format!("高于约束上限 {}", high + 1), false, self.int_value(base, high + 1)));
+                } else {
+                    cases.push((format!("取最大值 {}", type_max), true, self.int_value(base, type_max)));
+                    if base != "i64" {
+                        cases.push((format!("超出类型范围 {}", type_max + 1), false, json!(type_max + 1)));
+                    }
+                }
+            }
+            TypeExpr::Base(base) if base == "double" => {
+                for (bound, inclusive, below) in
+                    [(c.ge, true, true), (c.gt, false, true), (c.le, true, false), (c.lt, false, false)]
+                {
+                    let Some(bound) = bound else { continue };
+                    let side = if below { "下限" } else { "上限" };
+                    if inclusive {
+                        let outside = if below { bound - 1.0 } else { bound + 1.0 };
+                        cases.push((format!("取约束{} {}", side, bound), true, json!(bound)));
+                        cases.push((format!("超出约束{} {}", side, outside), false, json!(outside)));
+                    } else {
+                        cases.push((format!("等于开区间{} {}", side, bound), false, json!(bound)));
+                    }
+                }
+            }
+            TypeExpr::Base(base) if base == "string" => {
+                if let Some(pattern) = &c.pattern {
+                    let re = Regex::new(pattern).ok();
+                    if let Some(sample) = pattern_sample(pattern) {
+                        cases.push(("匹配 pattern".to_string(), true, json!(sample)));
+                    }
+                    if let Some(re) = re
+                        && let Some(other) = ["", "x", "0", "-"].into_iter().find(|s| !re.is_match(s))
+                    {
+                        cases.push(("不匹配 pattern".to_string(), false, json!(other)));
+                    }
+                    return cases;
+                }
+                let mut text = |label: String, accept: bool, len: usize| {
+                    if len <= MAX_SIZE {
+                        cases.push((label, accept, json!("x".repeat(len))));
+                    }
+                };
+                match c.min_size.unwrap_or(0) {
+                    0 => text("空字符串".to_string(), true, 0),
+                    min => {
+                        text(format!("长度为最小长度 {}", min), true, min);
+                        text(format!("长度小于最小长度 {}", min), false, min - 1);
+                    }
+                }
+                match c.max_size {
+                    Some(max) => {
+                        text(format!("长度为最大长度 {}", max), true, max);
+                        text(format!("长度超过最大长度 {}", max), false, max.saturating_add(1));
+                    }
+                    None => text(format!("长字符串（{} 个字符）", LONG_STRING), true, LONG_STRING),
+                }
+            }
+            TypeExpr::List(_) | TypeExpr::Set(_) | TypeExpr::Map(..) => {
+                let (empty_label, large_label, empty) = match &ty {
+                    TypeExpr::List(_) => ("空列表", "大列表", json!([])),
+                    TypeExpr::Set(_) => ("空 set", "大 set", json!([])),
+                    _ => ("空 map", "大 map", json!({})),
+                };
+                match c.min_size.unwrap_or(0) {
+                    0 => cases.push((empty_label.to_string(), true, empty)),
+                    min => {
+                        if let Some(value) = self.sized_container(&ty, min) {
+                            cases.push((format!("元素个数为最小值 {}", min), true, value));
+                        }
+                        if let Some(value) = self.sized_container(&ty, min - 1) {
+                            cases.push((format!("元素个数少于最小值 {}", min), false, value));
+                        }
+                    }
+                }
+                match c.max_size {
+                    Some(max) => {
+                        if let Some(value) = self.sized_container(&ty, max) {
+                            cases.push((format!("元素个数为最大值 {}", max), true, value));
+                        }
+                        if let Some(value) = self.sized_container(&ty, max.saturating_add(1)) {
+                            cases.push((format!("元素个数超过最大值 {}", max), false, value));
+                        }
+                    }
+                    // 元素为结构体时大容器过于庞大，只对元素为基础类型或枚举的容器生成
+                    None if self.is_scalar_container(&ty) => {
+                        if let Some(value) = self.sized_container(&ty, LARGE_SIZE) {
+                            cases.push((format!("{}（{} 个元素）", large_label, LARGE_SIZE), true, value));
+                        }
+                    }
+                    None => {}
+                }
+            }
+            TypeExpr::Named(name) if self.enums.contains_key(name.as_str()) => {
+                let invalid = self.enums[name.as_str()].values.iter().map(|v| v.value).max().unwrap_or_default() + 1;
+                cases.push((format!("无效的枚举值 {}", invalid), false, json!(invalid)));
+            }
+            _ => {}
+        }
+        cases
+    }
+
+    /// 整数示例值，i64 按 `sample.i64` 以数字或字符串表示
+    fn int_value(&self, base: &str, n: i64) -> Value {
+        if base == "i64" && self.config.i64 == "string" { json!(n.to_string()) } else { json!(n) }
+    }
+
+    /// 不在 vt.in 可选值中的值，找不到时返回 None
+    fn disallowed_value(&self, allowed: &[Value], ty: &TypeExpr) -> Option<Value> {
+        let max_int = allowed
+            .iter()
+            .filter_map(|v| v.as_i64().or_else(|| v.as_str().and_then(|s| s.parse().ok())))
+            .max()
+            .unwrap_or_default();
+        let value = match ty {
+            TypeExpr::Base(base) if base == "string" || base == "binary" => json!("invalid"),
+            TypeExpr::Base(base) if base == "double" => {
+                json!(allowed.iter().filter_map(Value::as_f64).fold(0.0, f64::max) + 1.0)
+            }
+            TypeExpr::Base(base) if base == "bool" => json!(!allowed.first()?.as_bool()?),
+            TypeExpr::Base(base) => self.int_value(base, max_int.checked_add(1)?),
+            _ => json!(max_int.checked_add(1)?),
+        };
+        (!allowed.contains(&value)).then_some(value)
+    }
+
+    /// 元素个数为 len 的列表、set 或 map，len 超过 `MAX_SIZE` 或 set、map 无法生成足够多互不相同的元素（或 key）时返回 None
+    fn sized_container(&mut self, ty: &TypeExpr, len: usize) -> Option<Value> {
+        if len > MAX_SIZE {
+            return None;
+        }
+        match ty {
+            TypeExpr::List(inner) => Some(Value::Array((0..len).map(|n| self.sample_value(inner, n)).collect())),
+            TypeExpr::Set(inner) => {
+                let mut values = Vec::new();
+                for n in 0..len * 3 {
+                    if values.len() == len {
+                        break;
+                    }
+                    let value = self.sample_value(inner, n);
+                    if !values.contains(&value) {
+                        values.push(value);
+                    }
+                }
+                (values.len() == len).then_some(Value::Array(values))
+            }
+            TypeExpr::Map(key_type, value_type) => {
+                let mut map = serde_json::Map::new();
+                for n in 0..len * 3 {
+                    if map.len() == len {
+                        break;
+                    }
+                    let key = match self.sample_value(key_type, n) {
+                        Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    if !map.contains_key(&key) {
+                        let value = self.sample_value(value_type, n);
+                        map.insert(key, value);
+                    }
+                }
+                (map.len() == len).then_some(Value::Object(map))
+            }
+            _ => None,
+        }
+    }
+
+    /// 容器的元素（map 的 key 和 value）是否都是基础类型或枚举
+    fn is_scalar_container(&self, ty: &TypeExpr) -> bool {
+        let scalar = |ty: &TypeExpr| match self.resolve(ty) {
+            TypeExpr::Base(_) => true,
+            TypeExpr::Named(name) => self.enums.contains_key(name.as_str()),
+            _ => false,
+        };
+        match ty {
+            TypeExpr::List(inner) | TypeExpr::Set(inner) => scalar(inner),
+            TypeExpr::Map(key, value) => scalar(key) && scalar(value),
+            _ => false,
+        }
+    }
+
+    /// 与类型不符的值：应为字符串的类型取数字，其它类型取字符串；无法确定类型（include 文件中的类型）时返回 None
+    fn wrong_type_value(&self, ty: &TypeExpr) -> Option<Value> {
+        match self.resolve(ty) {
+            TypeExpr::Base(base) if base == "void" => None,
+            TypeExpr::Base(base) if matches!(base.as_str(), "string" | "binary" | "uuid") => Some(json!(12345)),
+            TypeExpr::Named(name)
+                if !self.structs.contains_key(name.as_str()) && !self.enums.contains_key(name.as_str()) =>
+            {
+                None
+            }
+            _ => Some(json!("example")),
+        }
+    }
+
+    /// 方法参数或返回值的示例：结构体生成对象，其它类型按类型生成，没有参数或返回 void 时为 null
+    fn method_value(&mut self, s: &MyStruct) -> Value {
+        match self.structs.get(s.name.as_str()) {
+            Some(&st) => self.generate_sample_for_struct(st),
+            None if s.name.is_empty() => Value::Null,
+            None => self.get_sample_value(&s.name),
+        }
+    }
+}
+
+/// 随机模式下基础类型的示例值，整数取值均在对应类型的范围内
+fn random_base_value(rng: &mut Rng, base: &str, i64_as_string: bool) -> Value {
+    match base {
+        "bool" => json!(rng.next() & 1 == 0),
+        "byte" | "i8" => json!(rng.range(0, i8::MAX as i64)),
+        "i16" => json!(rng.range(0, i16::MAX as i64)),
+        "i32" => json!(rng.range(0, 1_000_000)),
+        "i64" if i64_as_string => json!(rng.range(0, 10_000_000_000_000).to_string()),
+        "i64" => json!(rng.range(0, 10_000_000_000_000)),
+        // 保留两位小数
+        "double" => json!(rng.range(0, 100_000) as f64 / 100.0),
+        "binary" => json!(encode_base64(&rng.bytes(6))),
+        "uuid" => {
+            // 版本 4 UUID
+            let mut b = rng.bytes(16);
+            b[6] = b[6] & 0x0f | 0x40;
+            b[8] = b[8] & 0x3f | 0x80;
+            let hex: String = b.iter().map(|b| format!("{:02x}", b)).collect();
+            json!(format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..]))
+        }
+        "void" => Value::Null,
+        _ => json!(format!("{}_{}", WORDS[rng.range(0, WORDS.len() as i64 - 1) as usize], rng.range(1, 999))),
+    }
+}
+
+/// 整数不在约束范围内时取范围内的值：有上下限时取中间值，否则取边界值；
+/// 取值同时限制在整数类型的范围内，约束与类型范围没有交集时只按类型范围调整
+fn int_in_range(n: i64, base: &str, c: &Constraints) -> i64 {
+    let (type_min, type_max) = int_type_range(base);
+    let Some((low, high)) = int_range(base, c) else {
+        return n.clamp(type_min, type_max);
+    };
+    if (low..=high).contains(&n) {
+        return n;
+    }
+    match int_bounds(c) {
+        (Some(_), Some(_)) => ((low as i128 + high as i128) / 2) as i64,
+        (Some(_), None) => low,
+        (None, Some(_)) => high,
+        (None, None) => n.clamp(low, high),
+    }
+}
+
+/// 约束与整数类型取值范围的交集 (最小值, 最大值)，没有满足约束的整数时返回 None
+fn int_range(base: &str, c: &Constraints) -> Option<(i64, i64)> {
+    let (type_min, type_max) = int_type_range(base);
+    let (low, high) = int_bounds(c);
+    let low = low.map_or(type_min as i128, |low| low.max(type_min as i128));
+    let high = high.map_or(type_max as i128, |high| high.min(type_max as i128));
+    // 交集在类型范围内，转换为 i64 不会溢出
+    (low <= high).then_some((low as i64, high as i64))
+}
+
+/// 约束允许的最小、最大整数，边界可以超出 i64 的范围（例如 `vt.gt = "1e19"`）
+fn int_bounds(c: &Constraints) -> (Option<i128>, Option<i128>) {
+    let gt = c.gt.map(|b| (b.floor() as i128).saturating_add(1));
+    let lt = c.lt.map(|b| (b.ceil() as i128).saturating_sub(1));
+    let low = c.ge.map(|b| b.ceil() as i128).into_iter().chain(gt).max();
+    let high = c.le.map(|b| b.floor() as i128).into_iter().chain(lt).min();
+    (low, high)
+}
+
+/// 整数类型的取值范围
+fn int_type_range(base: &str) -> (i64, i64) {
+    match base {
+        "byte" | "i8" => (i8::MIN as i64, i8::MAX as i64),
+        "i16" => (i16::MIN as i64, i16::MAX as i64),
+        "i32" => (i32::MIN as i64, i32::MAX as i64),
+        _ => (i64::MIN, i64::MAX),
+    }
+}
+
+/// 一个测试用例，`accept` 为预期服务接受（true）还是拒绝（false）该请求
+fn test_case(name: String, kind: &str, accept: bool, request: Value) -> Value {
+    json!({
+        "name": name,
+        "kind": kind,
+        "expect": if accept { "accept" } else { "reject" },
+        "request": request,
+    })
+}
+
+/// 小数不在约束范围内时取范围内的值：有上下限时取中间值，只有开区间的边界时与边界相差 1
+fn float_in_range(n: f64, c: &Constraints) -> f64 {
+    let above = c.gt.is_none_or(|b| n > b) && c.ge.is_none_or(|b| n >= b);
+    let below = c.lt.is_none_or(|b| n < b) && c.le.is_none_or(|b| n <= b);
+    if above && below {
+        return n;
+    }
+    let low = c.gt.or(c.ge);
+    let high = c.lt.or(c.le);
+    match (low, high) {
+        (Some(low), Some(high)) => (low + high) / 2.0,
+        (Some(_), None) => c.ge.unwrap_or_else(|| c.gt.unwrap_or_default() + 1.0),
+        (None, Some(_)) => c.le.unwrap_or_else(|| c.lt.unwrap_or_default() - 1.0),
+        (None, None) => n,
+    }
+}
+
+/// 生成一个匹配正则表达式的字符串：字符类优先取字母或数字，重复取最少次数（至少一次），分支取第一个；
+/// 正则表达式无法解析时返回 None
+fn pattern_sample(pattern: &str) -> Option<String> {
+    let hir = regex_syntax::Parser::new().parse(pattern).ok()?;
+    let mut sample = String::new();
+    write_pattern_sample(&hir, &mut sample);
+    Some(sample)
+}
+
+fn write_pattern_sample(hir: &Hir, out: &mut String) {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => {}
+        HirKind::Literal(literal) => out.push_str(&String::from_utf8_lossy(&literal.0)),
+        HirKind::Class(Class::Unicode(class)) => {
+            let ranges: Vec<(char, char)> = class.ranges().iter().map(|r| (r.start(), r.end())).collect();
+            let preferred = "a0A_-.@".chars().find(|c| ranges.iter().any(|(start, end)| start <= c && c <= end));
+            out.extend(preferred.or(ranges.first().map(|r| r.0)));
+        }
+        HirKind::Class(Class::Bytes(class)) => out.extend(class.ranges().first().map(|r| r.start() as char)),
+        HirKind::Repetition(rep) => {
+            for _ in 0..rep.min.max(1).min(rep.max.unwrap_or(u32::MAX)) {
+                write_pattern_sample(&rep.sub, out);
+            }
+        }
+        HirKind::Capture(capture) => write_pattern_sample(&capture.sub, out),
+        HirKind::Concat(hirs) => hirs.iter().for_each(|h| write_pattern_sample(h, out)),
+        HirKind::Alternation(hirs) => hirs.iter().take(1).for_each(|h| write_pattern_sample(h, out)),
     }
-    Value::Object(map)
+}
+
+/// 驼峰命名转换为下划线命名并转为小写，例如 `createdAt` 转换为 `created_at`
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.char_indices() {
+        if c.is_uppercase() && i > 0 && !name[..i].ends_with('_') {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// 字段名是否匹配规则中的模式，`*` 匹配任意个字符
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+    if !name.starts_with(first) || name.len() < first.len() + last.len() || !name.ends_with(last) {
+        return false;
+    }
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// 第 n 个字符串示例值：example、example2、example3……
+fn sample_string(n: usize) -> String {
+    if n == 0 { "example".to_string() } else { format!("example{}", n + 1) }
+}
+
+/// Thrift JSON 协议中 binary 以 base64 字符串表示
+fn encode_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], chunk.get(1).copied().unwrap_or(0), chunk.get(2).copied().unwrap_or(0)];
+        let triple = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(triple >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
 }
 
 /// 根据解析后的 File 对象生成示例数据
 ///
 /// 生成的 JSON 对象按照服务 -> 方法 -> { request, response } 的层级组织，
-/// 如果找不到对应的结构体，则对应示例数据为 null。
-pub fn generate_sample_data(file: &File) -> String {
-    // 通过已有函数提取所有服务
-    let services = extract_services(file);
-    // 收集所有结构体定义，便于查找，注意 collect_structs 需返回 Vec<MyStruct>
-    let structs = collect_structs(file);
-    let mut struct_map: HashMap<String, MyStruct> = HashMap::new();
-    for s in &structs {
-        struct_map.insert(s.name.clone(), (*s).clone());
-    }
+/// 没有参数或返回 void 时对应示例数据为 null。`sample.optional` 为 both 时 request、response 中填充了所有可选字段，
+/// 另外的 request_minimal、response_minimal 省略了所有可选字段。
+pub fn generate_sample_values(file: &File) -> Value {
+    generate_samples(file, false).0
+}
+
+/// 与 `generate_sample_values` 相同，同时返回因递归层数达到 `sample.max_depth` 而截断的结构体名称；
+/// `cases` 为 true 时每个方法另外包含 `cases` 测试用例列表（见 `Sampler::test_cases`），用于 `_test.md`
+///
+/// 配置了 `sample.seed` 时生成随机的示例值，每个方法使用由种子和 `服务名.方法名` 得到的种子，
+/// 同一种子总是生成相同的结果，修改一个方法也不会影响其它方法的示例。
+/// 递归引用的结构体（例如 `struct TreeNode { 1: list<TreeNode> children }`）在同一条展开路径上最多展开 max_depth 层，
+/// 之后列表、set、map 生成空容器，直接引用的字段生成 null。
+pub fn generate_samples(file: &File, cases: bool) -> (Value, Vec<String>) {
+    document_samples(&build_document(file), cases)
+}
+
+/// 与 `generate_sample_values` 相同，但基于已整理好的 Document 生成，
+/// 传入展开 include 后的 Document（`resolver::load_resolved_document`）时 include 文件中的类型也能生成示例
+pub fn generate_document_samples(doc: &Document) -> Value {
+    document_samples(doc, false).0
+}
+
+fn document_samples(doc: &Document, cases: bool) -> (Value, Vec<String>) {
+    let mut sampler = Sampler::new(doc, &config::get().sample);
 
     let mut result = serde_json::Map::new();
     // 针对每个服务生成示例数据
-    for service in services {
+    for service in &doc.services {
         let mut service_obj = serde_json::Map::new();
-        for method in service.methods {
-            let request_value = if struct_map.contains_key(&method.request.name) {
-                generate_sample_for_struct(struct_map.get(&method.request.name).unwrap(), &struct_map)
-            } else {
-                json!(null)
-            };
-            let response_value = if struct_map.contains_key(&method.response.name) {
-                generate_sample_for_struct(struct_map.get(&method.response.name).unwrap(), &struct_map)
-            } else {
-                json!(null)
-            };
+        for method in &service.methods {
+            if let Some(seed) = sampler.config.seed {
+                sampler.rng = Some(Rng(seed ^ fnv1a(&format!("{}.{}", service.name, method.name))));
+            }
+            let mut sample = serde_json::Map::new();
+            if sampler.config.optional == "both" {
+                sampler.optional = "omit";
+                sample.insert("request_minimal".to_string(), sampler.method_value(&method.request));
+                sample.insert("response_minimal".to_string(), sampler.method_value(&method.response));
+                sampler.optional = "fill";
+            }
+            sample.insert("request".to_string(), sampler.method_value(&method.request));
+            sample.insert("response".to_string(), sampler.method_value(&method.response));
+            if cases {
+                let request = sample["request"].clone();
+                sample.insert("cases".to_string(), Value::Array(sampler.test_cases(&method.request, &request)));
+            }
             // 使用方法名称作为 key
-            service_obj.insert(
-                method.name,
-                json!({
-                    "request": request_value,
-                    "response": response_value,
-                }),
-            );
-        }
-        result.insert(service.name, Value::Object(service_obj));
-    }
-    Value::Object(result).to_string()
-}
\ No newline at end of file
+            service_obj.insert(method.name.clone(), Value::Object(sample));
+        }
+        result.insert(service.name.clone(), Value::Object(service_obj));
+    }
+    (Value::Object(result), sampler.truncated.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl_parser::parse_idl;
+
+    #[test]
+    fn test_base_type_samples() {
+        let file = parse_idl(
+            r#"
+            enum Status { ON = 1, OFF = 2 }
+            typedef i16 Age
+
+            struct Item {
+                1: required i8 a,
+                2: required i16 b,
+                3: required i32 c,
+                4: required i64 d,
+                5: required double e,
+                6: required byte f,
+                7: required binary g,
+                8: required uuid h,
+                9: required bool i,
+                10: required string j,
+                11: required Status status,
+                12: required Age age,
+                13: required list<i32> ids,
+            }
+
+            service ItemService {
+                Item GetItem (1: i64 id),
+                void Ping (),
+            }
+            "#,
+        )
+        .unwrap();
+        let samples = generate_sample_values(&file);
+        let item = &samples["ItemService"]["GetItem"]["response"];
+        assert_eq!(item["a"], json!(12));
+        assert_eq!(item["b"], json!(1234));
+        assert_eq!(item["c"], json!(123456));
+        assert_eq!(item["d"], json!(1234567890123_i64));
+        assert_eq!(item["e"], json!(12.5));
+        assert_eq!(item["f"], json!(12));
+        assert_eq!(item["g"], json!("ZXhhbXBsZQ=="));
+        assert_eq!(item["h"], json!("123e4567-e89b-12d3-a456-426614174000"));
+        assert_eq!(item["i"], json!(true));
+        assert_eq!(item["j"], json!("example"));
+        assert_eq!(item["status"], json!(1));
+        assert_eq!(item["age"], json!(1234));
+        assert_eq!(item["ids"], json!([123456]));
+        assert_eq!(samples["ItemService"]["GetItem"]["request"], json!(1234567890123_i64));
+        assert_eq!(samples["ItemService"]["Ping"], json!({"request": null, "response": null}));
+
+        let doc = build_document(&file);
+        let config = SampleConfig { i64: "string".to_string(), ..Default::default() };
+        let mut sampler = Sampler::new(&doc, &config);
+        assert_eq!(sampler.get_sample_value("list<i64>"), json!(["1234567890123"]));
+    }
+
+    #[test]
+    fn test_container_samples() {
+        let file = parse_idl(
+            r#"
+            enum Status { ON = 1, OFF = 2 }
+            struct Tag { 1: required string name }
+            "#,
+        )
+        .unwrap();
+        let doc = build_document(&file);
+        let config = SampleConfig::default();
+        let mut sampler = Sampler::new(&doc, &config);
+        assert_eq!(sampler.get_sample_value("map<string, i32>"), json!({"example": 123456}));
+        assert_eq!(sampler.get_sample_value("map<i64, Tag>"), json!({"1234567890123": {"name": "张三"}}));
+        assert_eq!(sampler.get_sample_value("map<Status, list<bool>>"), json!({"1": [true]}));
+        assert_eq!(sampler.get_sample_value("set<string>"), json!(["example"]));
+
+        // set 与列表一样有 list_min 个元素，且元素互不相同
+        let config = SampleConfig { list_min: 2, ..Default::default() };
+        let mut sampler = Sampler::new(&doc, &config);
+        assert_eq!(sampler.get_sample_value("list<string>"), json!(["example", "example2"]));
+        assert_eq!(sampler.get_sample_value("set<string>"), json!(["example", "example2"]));
+        assert_eq!(sampler.get_sample_value("set<Status>"), json!([1, 2]));
+        assert_eq!(sampler.get_sample_value("set<Tag>"), json!([{"name": "张三"}]));
+        assert_eq!(sampler.get_sample_value("set<binary>"), json!(["ZXhhbXBsZQ==", "ZXhhbXBsZTI="]));
+    }
+
+    #[test]
+    fn test_recursive_samples() {
+        let file = parse_idl(
+            r#"
+            struct TreeNode {
+                1: required string name,
+                2: required list<TreeNode> children,
+                3: required map<string, TreeNode> index,
+            }
+            struct A { 1: required B b }
+            struct B { 1: required A a, 2: required i32 id }
+            service TreeService {
+                TreeNode GetTree (1: A req),
+            }
+            "#,
+        )
+        .unwrap();
+        let (samples, truncated) = generate_samples(&file, false);
+        assert_eq!(truncated, vec!["A", "TreeNode"]);
+        let tree = &samples["TreeService"]["GetTree"]["response"];
+        assert_eq!(tree["children"][0]["children"][0]["name"], json!("张三"));
+        assert_eq!(tree["children"][0]["children"][0]["children"], json!([]));
+        assert_eq!(tree["index"]["example"]["index"]["example"]["index"], json!({}));
+        let a = &samples["TreeService"]["GetTree"]["request"];
+        assert_eq!(a["b"]["a"]["b"]["a"]["b"]["a"], json!(null));
+        assert_eq!(a["b"]["a"]["b"]["a"]["b"]["id"], json!(10001));
+    }
+
+    #[test]
+    fn test_optional_modes() {
+        let file = parse_idl(
+            r#"
+            struct Item {
+                1: required i32 id,
+                2: optional string title,
+                3: i32 count,
+            }
+            service ItemService {
+                Item GetItem (1: Item req),
+            }
+            "#,
+        )
+        .unwrap();
+        let doc = build_document(&file);
+        let item = &doc.structs[0];
+        for (mode, expected) in [
+            ("omit", json!({"id": 10001, "count": 123456})),
+            ("null", json!({"id": 10001, "title": null, "count": 123456})),
+            ("fill", json!({"id": 10001, "title": "example", "count": 123456})),
+        ] {
+            let config = SampleConfig { optional: mode.to_string(), ..Default::default() };
+            let mut sampler = Sampler::new(&doc, &config);
+            assert_eq!(sampler.generate_sample_for_struct(item), expected, "{}", mode);
+        }
+    }
+
+    #[test]
+    fn test_field_name_rules() {
+        let file = parse_idl(
+            r#"
+            typedef string Email
+            struct User {
+                1: required i64 user_id,
+                2: required string order_id,
+                3: required Email email,
+                4: required i64 createdAt,
+                5: required string updated_at,
+                6: required double price,
+                7: required i32 total_price,
+                8: required i8 shop_id,
+                9: required string nickname,
+                10: required string avatar_url,
+            }
+            "#,
+        )
+        .unwrap();
+        let doc = build_document(&file);
+        let config: SampleConfig = toml::from_str(
+            r#"
+            [[rules]]
+            field = "nick*"
+            value = "小明"
+            "#,
+        )
+        .unwrap();
+        let mut sampler = Sampler::new(&doc, &config);
+        assert_eq!(
+            sampler.generate_sample_for_struct(&doc.structs[0]),
+            json!({
+                "user_id": 10001,
+                "order_id": "10001",
+                "email": "user@example.com",
+                "createdAt": 1700000000,
+                "updated_at": "2023-11-14T22:13:20Z",
+                "price": 99.9,
+                "total_price": 9990,
+                "shop_id": 12,
+                "nickname": "小明",
+                "avatar_url": "https://example.com",
+            })
+        );
+        assert!(matches_pattern("*_id", "user_id"));
+        assert!(!matches_pattern("*_id", "id"));
+        assert!(matches_pattern("a*b*c", "axxbyyc"));
+        assert!(!matches_pattern("a*b*c", "ac"));
+    }
+
+    #[test]
+    fn test_seeded_samples() {
+        let file = parse_idl(
+            r#"
+            enum Status { ON = 1, OFF = 2, DELETED = 3 }
+            struct Item {
+                1: required i8 level,
+                2: required string title,
+                3: required list<i32> ids,
+                4: required set<Status> statuses,
+                5: required map<string, double> prices,
+                6: required uuid token,
+            }
+            "#,
+        )
+        .unwrap();
+        let doc = build_document(&file);
+        let sample = |seed: u64| {
+            let config = SampleConfig { seed: Some(seed), list_min: 2, list_max: 4, ..Default::default() };
+            let mut sampler = Sampler::new(&doc, &config);
+            sampler.rng = Some(Rng(seed));
+            sampler.generate_sample_for_struct(&doc.structs[0])
+        };
+        let item = sample(7);
+        assert_eq!(item, sample(7));
+        assert_ne!(item, sample(8));
+
+        let level = item["level"].as_i64().unwrap();
+        assert!((0..=127).contains(&level));
+        assert!(item["title"].is_string());
+        let ids = item["ids"].as_array().unwrap();
+        assert!((2..=4).contains(&ids.len()));
+        assert!(ids.iter().all(|id| id.is_i64()));
+        let statuses = item["statuses"].as_array().unwrap();
+        assert!(statuses.iter().all(|s| [1, 2, 3].contains(&s.as_i64().unwrap())));
+        assert!(statuses.iter().enumerate().all(|(i, s)| !statuses[..i].contains(s)));
+        assert!(item["prices"].as_object().unwrap().values().all(|p| p.is_f64() || p.is_i64()));
+        let token = item["token"].as_str().unwrap();
+        assert_eq!(token.len(), 36);
+        assert_eq!(&token[14..15], "4");
+    }
+
+    #[test]
+    fn test_default_values() {
+        let file = parse_idl(
+            r#"
+            const i32 DEFAULT_PAGE_SIZE = 20
+            const list<string> DEFAULT_TAGS = ["new", "hot"]
+            const i32 LOOP_A = LOOP_B
+            const i32 LOOP_B = LOOP_A
+            enum Status { ON = 1, OFF = 2 }
+            struct Range { 1: required i32 low, 2: required i32 high }
+            struct Query {
+                1: optional i32 page_size = 20,
+                2: optional i32 limit = DEFAULT_PAGE_SIZE,
+                3: required list<string> tags = DEFAULT_TAGS,
+                4: required map<i32, string> names = {1: "one"},
+                5: required Range range = {"high": 100},
+                6: required Status status = Status.OFF,
+                7: required double ratio = 1,
+                8: required bool enabled = 0,
+                9: optional string keyword,
+                10: required i32 size = LOOP_A,
+            }
+            "#,
+        )
+        .unwrap();
+        let doc = build_document(&file);
+        let config = SampleConfig::default();
+        let mut sampler = Sampler::new(&doc, &config);
+        let query = doc.structs.iter().find(|s| s.name == "Query").unwrap();
+        assert_eq!(
+            sampler.generate_sample_for_struct(query),
+            json!({
+                "page_size": 20,
+                "limit": 20,
+                "tags": ["new", "hot"],
+                "names": {"1": "one"},
+                "range": {"low": 123456, "high": 100},
+                "status": 2,
+                "ratio": 1.0,
+                "enabled": false,
+                "keyword": null,
+                // 循环引用的常量按类型生成
+                "size": 123456,
+            })
+        );
+    }
+
+    #[test]
+    fn test_constrained_samples() {
+        let file = parse_idl(
+            r#"
+            enum Status { ON = 1, OFF = 2 }
+            struct Query {
+                1: required i32 page (vt.ge = "1", vt.le = "100"),
+                2: required i64 offset (vt.lt = "0"),
+                3: required double ratio (vt.gt = "0", vt.lt = "1"),
+                4: required string code (vt.pattern = "^[A-Z]{3}-\\d{4}$"),
+                5: required string title (vt.min_size = "10"),
+                6: required string short (vt.max_size = "3"),
+                7: required list<i32> ids (vt.min_size = "3"),
+                8: required string lang (vt.in = "\"en\"", vt.in = "\"zh\""),
+                9: required Status status (vt.in = "[OFF]"),
+                10: required map<string, i32> extra (vt.min_size = "2"),
+            }
+            "#,
+        )
+        .unwrap();
+        let doc = build_document(&file);
+        let query = &doc.structs[0];
+        assert_eq!(query.fields[3].constraints.pattern.as_deref(), Some("^[A-Z]{3}-\\d{4}$"));
+        assert_eq!(query.fields[8].constraints.r#in, vec!["OFF"]);
+        assert_eq!(query.fields[0].constraints.describe(), "取值 ≥ 1 且 ≤ 100");
+
+        let config = SampleConfig::default();
+        let mut sampler = Sampler::new(&doc, &config);
+        let sample = sampler.generate_sample_for_struct(query);
+        assert_eq!(sample["page"], json!(50));
+        assert_eq!(sample["offset"], json!(-1));
+        assert_eq!(sample["ratio"], json!(0.5));
+        assert_eq!(sample["code"], json!("AAA-0000"));
+        assert_eq!(sample["title"], json!("examplexxx"));
+        assert_eq!(sample["short"], json!("exa"));
+        assert_eq!(sample["ids"], json!([123456, 123457, 123458]));
+        assert_eq!(sample["lang"], json!("en"));
+        assert_eq!(sample["status"], json!(2));
+        assert_eq!(sample["extra"].as_object().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_constraint_edge_cases() {
+        let file = parse_idl(
+            r#"
+            struct Query {
+                1: required i64 big (vt.gt = "1e19"),
+                2: required i64 small (vt.lt = "-1e19"),
+                3: required i64 wide (vt.ge = "-9e18", vt.le = "9e18"),
+                4: required string tag (vt.in = "[\"a,b\", 'c, d', \"e\"]"),
+                5: required i8 level (vt.ge = "200"),
+                6: required i8 rank (vt.ge = "100", vt.le = "1000"),
+            }
+            service QueryService {
+                void Query (1: Query req),
+            }
+            "#,
+        )
+        .unwrap();
+        let doc = build_document(&file);
+        let query = &doc.structs[0];
+        // 引号中的逗号不作为分隔符
+        assert_eq!(query.fields[3].constraints.r#in, vec!["\"a,b\"", "'c, d'", "\"e\""]);
+
+        let config = SampleConfig::default();
+        let mut sampler = Sampler::new(&doc, &config);
+        let sample = sampler.generate_sample_for_struct(query);
+        // 约束超出类型范围时无法满足，保留按类型生成的值
+        assert_eq!(sample["big"], json!(1234567890123_i64));
+        assert_eq!(sample["small"], json!(1234567890123_i64));
+        assert_eq!(sample["wide"], json!(1234567890123_i64));
+        assert_eq!(sample["tag"], json!("a,b"));
+        assert_eq!(sample["level"], json!(12));
+        assert_eq!(sample["rank"], json!(113));
+        assert_eq!(int_in_range(i64::MIN, "i64", &query.fields[2].constraints), 0);
+        assert_eq!(int_in_range(300, "i8", &Constraints::default()), 127);
+
+        let (samples, _) = generate_samples(&file, true);
+        let cases = samples["QueryService"]["Query"]["cases"].as_array().unwrap();
+        // 约束与类型范围没有交集时不生成可接受的边界值
+        let find = |name: &str| cases.iter().find(|c| c["name"] == name).unwrap_or_else(|| panic!("缺少用例 {}", name));
+        assert_eq!(find("big：约束与类型范围无交集 9223372036854775807")["expect"], "reject");
+        assert_eq!(find("small：约束与类型范围无交集 -9223372036854775808")["expect"], "reject");
+        assert_eq!(find("level：约束与类型范围无交集 127")["request"]["level"], json!(127));
+        for field in ["big", "small", "level"] {
+            let prefix = format!("{}：", field);
+            assert!(!cases.iter().any(|c| c["kind"] == "boundary"
+                && c["name"].as_str().unwrap().starts_with(&prefix)
+                && c["expect"] == "accept"));
+        }
+        assert_eq!(find("rank：取约束下限 100")["expect"], "accept");
+        assert_eq!(find("rank：取最大值 127")["expect"], "accept");
+        assert_eq!(find("rank：超出类型范围 128")["expect"], "reject");
+    }
+
+    #[test]
+    fn test_generate_test_cases() {
+        let file = parse_idl(
+            r#"
+            enum Status { ON = 1, OFF = 2 }
+            struct ListRequest {
+                1: required i32 page (vt.ge = "1", vt.le = "100"),
+                2: required string keyword (vt.max_size = "5"),
+                3: optional list<string> tags,
+                4: optional Status status,
+                5: optional i8 level,
+            }
+            service ItemService {
+                void List (1: ListRequest req),
+                void Get (1: i64 id),
+                void Ping (),
+            }
+            "#,
+        )
+        .unwrap();
+        let (samples, _) = generate_samples(&file, true);
+        let cases = samples["ItemService"]["List"]["cases"].as_array().unwrap();
+        let find = |name: &str| cases.iter().find(|c| c["name"] == name).unwrap_or_else(|| panic!("缺少用例 {}", name));
+
+        let case = find("page：取约束下限 1");
+        assert_eq!(case["kind"], "boundary");
+        assert_eq!(case["expect"], "accept");
+        assert_eq!(case["request"]["page"], json!(1));
+        assert_eq!(case["request"]["keyword"], json!("examp"));
+        assert_eq!(find("page：低于约束下限 0")["expect"], "reject");
+        assert_eq!(find("page：高于约束上限 101")["request"]["page"], json!(101));
+        assert_eq!(find("keyword：空字符串")["expect"], "accept");
+        assert_eq!(find("keyword：长度为最大长度 5")["request"]["keyword"], json!("xxxxx"));
+        assert_eq!(find("keyword：长度超过最大长度 5")["expect"], "reject");
+        assert_eq!(find("tags：空列表")["request"]["tags"], json!([]));
+        assert_eq!(find("tags：大列表（100 个元素）")["request"]["tags"].as_array().unwrap().len(), 100);
+        assert_eq!(find("status：无效的枚举值 3")["expect"], "reject");
+        assert_eq!(find("level：超出类型范围 128")["request"]["level"], json!(128));
+        assert_eq!(find("level：取最小值 -128")["expect"], "accept");
+        assert_eq!(find("省略所有可选字段")["request"], json!({"page": 50, "keyword": "examp"}));
+
+        let case = find("缺少必填字段 page");
+        assert_eq!(case["kind"], "missing_required");
+        assert_eq!(case["expect"], "reject");
+        assert!(case["request"].get("page").is_none());
+        assert!(cases.iter().all(|c| c["name"] != "缺少必填字段 tags"));
+
+        let case = find("keyword：类型错误");
+        assert_eq!(case["kind"], "wrong_type");
+        assert_eq!(case["request"]["keyword"], json!(12345));
+        assert_eq!(find("page：类型错误")["request"]["page"], json!("example"));
+
+        // 参数不是结构体时对整个参数生成用例，没有参数时没有用例
+        let get = samples["ItemService"]["Get"]["cases"].as_array().unwrap();
+        assert_eq!(get[0]["name"], "取最小值 -9223372036854775808");
+        assert_eq!(get[0]["request"], json!(i64::MIN));
+        assert_eq!(get.last().unwrap()["request"], json!("example"));
+        assert_eq!(samples["ItemService"]["Ping"]["cases"], json!([]));
+        assert!(generate_sample_values(&file)["ItemService"]["List"].get("cases").is_none());
+    }
+
+    #[test]
+    fn test_large_size_cases() {
+        let file = parse_idl(
+            r#"
+            struct UploadRequest {
+                1: required string content (vt.max_size = "65535"),
+                2: required string code (vt.max_size = "1000"),
+                3: required list<i32> ids (vt.min_size = "1000000"),
+                4: required map<string, string> extra (vt.max_size = "18446744073709551615"),
+            }
+            service FileService {
+                void Upload (1: UploadRequest req),
+            }
+            "#,
+        )
+        .unwrap();
+        let (samples, _) = generate_samples(&file, true);
+        let cases = samples["FileService"]["Upload"]["cases"].as_array().unwrap();
+        let names: Vec<&str> = cases.iter().filter_map(|c| c["name"].as_str()).collect();
+
+        // 超过 MAX_SIZE 的边界用例不生成
+        assert!(names.contains(&"content：空字符串"));
+        assert!(!names.iter().any(|n| n.starts_with("content：长度")));
+        assert!(names.contains(&"code：长度为最大长度 1000"));
+        assert!(!names.contains(&"code：长度超过最大长度 1000"));
+        assert!(!names.iter().any(|n| n.starts_with("ids：元素个数")));
+        assert!(names.contains(&"extra：空 map"));
+        assert!(!names.iter().any(|n| n.starts_with("extra：元素个数")));
+        // 示例值最多补足到 MAX_SIZE 个元素
+        assert_eq!(samples["FileService"]["Upload"]["request"]["ids"].as_array().unwrap().len(), MAX_SIZE);
+    }
+}
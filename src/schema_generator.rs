@@ -0,0 +1,251 @@
+use std::collections::BTreeSet;
+use serde_json::{json, Map, Value};
+use crate::idl_parser::{parse_type, Document, Enum, Field, Struct, TypeExpr};
+
+/// 导出的 JSON Schema 使用的规范版本
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+const DEFS_PREFIX: &str = "#/$defs/";
+
+/// 为 Document 中的所有结构体、枚举和 typedef 生成 JSON Schema 定义，key 为类型名
+/// `ref_prefix` 为引用其它类型时 `$ref` 的前缀，例如 `#/components/schemas/`
+pub fn definitions(doc: &Document, ref_prefix: &str) -> Map<String, Value> {
+    let mut defs = Map::new();
+    for s in &doc.structs {
+        defs.insert(s.name.clone(), struct_schema(s, ref_prefix));
+    }
+    for e in &doc.enums {
+        defs.insert(e.name.clone(), enum_schema(e));
+    }
+    for t in &doc.typedefs {
+        let mut schema = type_schema(&t.r#type, ref_prefix);
+        schema["title"] = json!(t.name);
+        describe(&mut schema, &t.comment);
+        defs.insert(t.name.clone(), schema);
+    }
+    defs
+}
+
+/// 将全部类型打包为一个 JSON Schema 文件，类型定义放在 `$defs` 中
+pub fn bundled_schema(doc: &Document, title: &str) -> Value {
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "title": title,
+        "$defs": definitions(doc, DEFS_PREFIX),
+    })
+}
+
+/// 为每个类型生成一份独立的 JSON Schema，返回 (类型名, Schema)
+///
+/// 类型本身的定义作为根 Schema，它（递归）引用到的其它类型放在 `$defs` 中，
+/// 因此每个文件都可以单独使用。
+pub fn type_schemas(doc: &Document) -> Vec<(String, Value)> {
+    let defs = definitions(doc, DEFS_PREFIX);
+    defs.iter()
+        .map(|(name, schema)| {
+            let mut referenced = BTreeSet::new();
+            let mut pending = vec![schema];
+            while let Some(schema) = pending.pop() {
+                let mut refs = Vec::new();
+                collect_refs(schema, &mut refs);
+                for r in refs {
+                    if let Some(def) = defs.get(&r)
+                        && referenced.insert(r)
+                    {
+                        pending.push(def);
+                    }
+                }
+            }
+
+            let mut root = schema.clone();
+            root["$schema"] = json!(JSON_SCHEMA_DIALECT);
+            if !referenced.is_empty() {
+                let used: Map<String, Value> = referenced
+                    .into_iter()
+                    .map(|r| (r.clone(), defs[&r].clone()))
+                    .collect();
+                root["$defs"] = Value::Object(used);
+            }
+            (name.clone(), root)
+        })
+        .collect()
+}
+
+/// 收集 Schema 中所有指向 `$defs` 的引用
+fn collect_refs(schema: &Value, refs: &mut Vec<String>) {
+    match schema {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value.as_str().and_then(|r| r.strip_prefix(DEFS_PREFIX)) {
+                    Some(name) if key == "$ref" => refs.push(name.to_string()),
+                    _ => collect_refs(value, refs),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+        _ => {}
+    }
+}
+
+/// 有文档注释时添加 description
+fn describe(schema: &mut Value, comment: &str) {
+    if !comment.is_empty() {
+        schema["description"] = json!(comment);
+    }
+}
+
+/// 将字段类型字符串转换为 JSON Schema
+pub fn type_schema(ty: &str, ref_prefix: &str) -> Value {
+    expr_schema(&parse_type(ty), ref_prefix)
+}
+
+fn expr_schema(expr: &TypeExpr, ref_prefix: &str) -> Value {
+    match expr {
+        TypeExpr::Base(base) => base_schema(base),
+        TypeExpr::List(value) => json!({
+            "type": "array",
+            "items": expr_schema(value, ref_prefix),
+        }),
+        TypeExpr::Set(value) => json!({
+            "type": "array",
+            "items": expr_schema(value, ref_prefix),
+            "uniqueItems": true,
+        }),
+        // JSON 对象的 key 只能是字符串，非字符串类型的 key 会被转换为字符串，整数 key 限制为数字形式
+        TypeExpr::Map(key, value) => {
+            let mut schema = json!({
+                "type": "object",
+                "additionalProperties": expr_schema(value, ref_prefix),
+            });
+            if let TypeExpr::Base(base) = key.as_ref()
+                && matches!(base.as_str(), "byte" | "i8" | "i16" | "i32" | "i64")
+            {
+                schema["propertyNames"] = json!({ "pattern": "^-?[0-9]+$" });
+            }
+            schema
+        }
+        TypeExpr::Named(name) => json!({ "$ref": format!("{}{}", ref_prefix, name) }),
+    }
+}
+
+fn base_schema(base: &str) -> Value {
+    match base {
+        "bool" => json!({ "type": "boolean" }),
+        "byte" | "i8" => json!({ "type": "integer", "format": "int32", "minimum": i8::MIN, "maximum": i8::MAX }),
+        "i16" => json!({ "type": "integer", "format": "int32", "minimum": i16::MIN, "maximum": i16::MAX }),
+        "i32" => json!({ "type": "integer", "format": "int32", "minimum": i32::MIN, "maximum": i32::MAX }),
+        "i64" => json!({ "type": "integer", "format": "int64", "minimum": i64::MIN, "maximum": i64::MAX }),
+        "double" => json!({ "type": "number", "format": "double" }),
+        "binary" => json!({ "type": "string", "contentEncoding": "base64" }),
+        "uuid" => json!({ "type": "string", "format": "uuid" }),
+        "void" => json!({ "type": "null" }),
+        _ => json!({ "type": "string" }),
+    }
+}
+
+/// 结构体与异常转换为 object；union 转换为 oneOf，每个分支只包含一个字段
+pub fn struct_schema(s: &Struct, ref_prefix: &str) -> Value {
+    if s.kind == "union" {
+        let variants: Vec<Value> = s
+            .fields
+            .iter()
+            .map(|f| {
+                json!({
+                    "type": "object",
+                    "properties": { f.name.clone(): field_schema(f, ref_prefix) },
+                    "required": [f.name],
+                    "additionalProperties": false,
+                })
+            })
+            .collect();
+        let mut schema = json!({ "title": s.name, "oneOf": variants });
+        describe(&mut schema, &s.comment);
+        return schema;
+    }
+
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for f in &s.fields {
+        properties.insert(f.name.clone(), field_schema(f, ref_prefix));
+        if f.attribute == "Required" {
+            required.push(json!(f.name));
+        }
+    }
+    let mut schema = json!({
+        "title": s.name,
+        "type": "object",
+        "properties": properties,
+    });
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+    describe(&mut schema, &s.comment);
+    schema
+}
+
+fn field_schema(f: &Field, ref_prefix: &str) -> Value {
+    let mut schema = type_schema(&f.r#type, ref_prefix);
+    describe(&mut schema, &f.comment);
+    schema
+}
+
+/// Thrift 枚举在 JSON 中以整数表示，名称通过 `x-enum-varnames` 给出
+pub fn enum_schema(e: &Enum) -> Value {
+    let mut schema = json!({
+        "title": e.name,
+        "type": "integer",
+        "enum": e.values.iter().map(|v| v.value).collect::<Vec<_>>(),
+        "x-enum-varnames": e.values.iter().map(|v| v.name.clone()).collect::<Vec<_>>(),
+    });
+    describe(&mut schema, &e.comment);
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl_parser::{build_document, parse_idl};
+    use crate::source_map::apply_source_info;
+
+    const SAMPLE_THRIFT: &str = r#"
+        enum Status { ON = 1, OFF = 2 }
+
+        /// 商品
+        struct Item {
+            /// 商品 ID
+            1: required i64 id,
+            2: optional i8 level,
+            3: optional map<i32, Tag> tags,
+            4: optional Status status,
+        }
+
+        struct Tag { 1: string name }
+
+        union Key { 1: i64 id, 2: string slug }
+    "#;
+
+    #[test]
+    fn test_json_schema() {
+        let mut doc = build_document(&parse_idl(SAMPLE_THRIFT).unwrap());
+        apply_source_info(&mut doc, SAMPLE_THRIFT);
+
+        let bundle = bundled_schema(&doc, "item");
+        assert_eq!(bundle["$schema"], JSON_SCHEMA_DIALECT);
+        let item = &bundle["$defs"]["Item"];
+        assert_eq!(item["description"], "商品");
+        assert_eq!(item["required"], json!(["id"]));
+        assert_eq!(item["properties"]["id"]["description"], "商品 ID");
+        assert_eq!(item["properties"]["level"]["minimum"], -128);
+        assert_eq!(item["properties"]["level"]["maximum"], 127);
+        assert_eq!(item["properties"]["tags"]["additionalProperties"]["$ref"], "#/$defs/Tag");
+        assert_eq!(item["properties"]["tags"]["propertyNames"]["pattern"], "^-?[0-9]+$");
+        assert_eq!(bundle["$defs"]["Status"]["enum"], json!([1, 2]));
+        assert_eq!(bundle["$defs"]["Key"]["oneOf"][0]["required"], json!(["id"]));
+
+        let schemas: Map<String, Value> = type_schemas(&doc).into_iter().collect();
+        assert_eq!(schemas["Item"]["$schema"], JSON_SCHEMA_DIALECT);
+        let defs: Vec<&String> = schemas["Item"]["$defs"].as_object().unwrap().keys().collect();
+        assert_eq!(defs, vec!["Status", "Tag"]);
+        assert!(schemas["Tag"].get("$defs").is_none());
+    }
+}
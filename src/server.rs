@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::config;
+use crate::html_generator::{self, escape_html};
+use crate::watcher::Watcher;
+
+/// 浏览器通过该地址订阅重新加载事件（Server-Sent Events）
+const LIVE_RELOAD_PATH: &str = "/__livereload";
+
+/// 注入到每个页面中的实时刷新脚本
+const LIVE_RELOAD_SCRIPT: &str = r#"<script>
+new EventSource("/__livereload").onmessage = function () { location.reload(); };
+</script>"#;
+
+/// 内存中的站点内容
+#[derive(Default)]
+struct Site {
+    /// 文件名 -> HTML 内容，渲染失败时保留上一次成功的结果
+    pages: HashMap<String, String>,
+    /// 最近一次渲染的错误信息，存在时以浮层形式显示在页面上
+    error: Option<String>,
+    /// 每次重新渲染后递增，用于通知浏览器刷新
+    version: u64,
+}
+
+/// 启动本地文档预览服务：在内存中渲染 HTML 文档，监听 IDL 与模板文件的变化并通知浏览器刷新
+pub fn serve(addr: &str) -> Result<(), String> {
+    let listener = TcpListener::bind(addr).map_err(|e| format!("绑定 {} 失败: {}", addr, e))?;
+    let site = Arc::new(Mutex::new(Site::default()));
+    render(&site);
+    println!("文档预览服务已启动: http://{}", addr);
+
+    // 后台轮询 IDL 与模板文件，发生变化时重新渲染
+    let mut watched: Vec<PathBuf> = config::get().idl_roots.iter().map(PathBuf::from).collect();
+    if let Some(dir) = &config::get().template_dir {
+        watched.push(PathBuf::from(dir));
+    }
+    let watcher_site = Arc::clone(&site);
+    thread::spawn(move || {
+        let mut watcher = Watcher::new(watched);
+        loop {
+            thread::sleep(Duration::from_millis(500));
+            let changed = watcher.poll();
+            if changed.is_empty() {
+                continue;
+            }
+            for path in &changed {
+                println!("检测到变更: {}", path.display());
+            }
+            render(&watcher_site);
+        }
+    });
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let site = Arc::clone(&site);
+        thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, &site) {
+                eprintln!("处理请求失败: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// 重新渲染全部文档；IDL 解析或模板渲染失败时保留旧页面并记录错误
+fn render(site: &Mutex<Site>) {
+    let result = config::get()
+        .idl_files()
+        .and_then(|paths| html_generator::load_pages(&paths))
+        .and_then(|pages| html_generator::generate_site(&pages));
+    let mut site = site.lock().unwrap();
+    match result {
+        Ok(files) => {
+            site.pages = files.into_iter().collect();
+            site.error = None;
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            site.error = Some(e);
+        }
+    }
+    site.version += 1;
+}
+
+fn handle_connection(mut stream: TcpStream, site: &Mutex<Site>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // 读取并丢弃剩余的请求头
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let path = path.split('?').next().unwrap_or("/");
+    if path == LIVE_RELOAD_PATH {
+        return stream_reload_events(stream, site);
+    }
+
+    let name = match path.trim_start_matches('/') {
+        "" => "index.html",
+        name => name,
+    };
+    let (page, error) = {
+        let site = site.lock().unwrap();
+        (site.pages.get(name).cloned(), site.error.clone())
+    };
+    let (status, body) = match (page, &error) {
+        (Some(html), _) => ("200 OK", html),
+        // 首次渲染就失败时还没有任何页面，返回一个只包含错误浮层的空白页
+        (None, Some(_)) => ("200 OK", "<!DOCTYPE html><html><body></body></html>".to_string()),
+        (None, None) => ("404 Not Found", not_found_page(name)),
+    };
+    let body = inject(&body, error.as_deref());
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+/// 保持连接并在站点版本变化时推送 reload 事件
+fn stream_reload_events(mut stream: TcpStream, site: &Mutex<Site>) -> std::io::Result<()> {
+    stream.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n",
+    )?;
+    let version = site.lock().unwrap().version;
+    let mut ticks = 0;
+    loop {
+        thread::sleep(Duration::from_millis(200));
+        if site.lock().unwrap().version != version {
+            stream.write_all(b"data: reload\n\n")?;
+            return Ok(());
+        }
+        // 定期发送注释行作为心跳，浏览器关闭页面后写入失败即可结束线程
+        ticks += 1;
+        if ticks % 50 == 0 {
+            stream.write_all(b": ping\n\n")?;
+        }
+    }
+}
+
+/// 在页面末尾注入实时刷新脚本，存在错误时同时注入错误浮层
+fn inject(html: &str, error: Option<&str>) -> String {
+    let mut snippet = String::new();
+    if let Some(error) = error {
+        snippet.push_str(&format!(
+            "<div style=\"position:fixed;inset:0;z-index:9999;overflow:auto;padding:32px;background:rgba(20,20,20,.92);color:#ff8080;font:14px/1.5 monospace;white-space:pre-wrap\"><strong>文档生成失败</strong>\n\n{}</div>",
+            escape_html(error)
+        ));
+    }
+    snippet.push_str(LIVE_RELOAD_SCRIPT);
+    match html.rfind("</body>") {
+        Some(pos) => format!("{}{}{}", &html[..pos], snippet, &html[pos..]),
+        None => format!("{}{}", html, snippet),
+    }
+}
+
+/// 404 页面，请求路径来自客户端，需要转义后再写入页面
+fn not_found_page(name: &str) -> String {
+    format!("<!DOCTYPE html><html><body>找不到页面 {}</body></html>", escape_html(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject_error_overlay() {
+        let html = inject("<html><body><p>doc</p></body></html>", Some("unexpected <token>"));
+        assert!(html.contains("文档生成失败"));
+        assert!(html.contains("unexpected &lt;token&gt;"));
+        assert!(html.contains(LIVE_RELOAD_PATH));
+        assert!(html.ends_with("</body></html>"));
+
+        let page = not_found_page("<script>alert(1)</script>.html");
+        assert!(page.contains("&lt;script&gt;alert(1)&lt;/script&gt;.html"));
+        assert!(!page.contains("<script>"));
+    }
+}
@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+use serde::Serialize;
+use crate::idl_parser::{Document, Struct};
+
+/// 定义在源码中的位置（从 1 开始的行号）及其文档注释
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct SourceInfo {
+    pub line: usize,
+    pub comment: String,
+}
+
+/// 扫描 IDL 源码，找出每个定义的行号和文档注释
+///
+/// pilota 的解析结果中不包含注释和位置信息，这里扫描源码作为补充。
+/// 返回的 key 为 `类型名`、`类型名.字段名`、`枚举名.枚举项`、`服务名`、`服务名.方法名`；
+/// 注释取定义前紧邻的注释块，没有时取同一行末尾的注释。字段、枚举项和方法按花括号内的分隔符
+/// （`,`、`;` 或换行）切分，因此写在一行中的定义（`struct Tag { 1: required string name }`）同样能找到。
+pub fn scan_source(content: &str) -> HashMap<String, SourceInfo> {
+    let mut result = HashMap::new();
+    let mut pending: Vec<String> = Vec::new();
+    let mut in_block = false;
+    // 当前所在的定义：(定义方式, 名称)
+    let mut scope: Option<(String, String)> = None;
+    let mut depth = 0i32;
+    let mut paren_depth = 0i32;
+    let mut angle_depth = 0i32;
+    let mut quote: Option<char> = None;
+    // 正在读取的成员（字段、枚举项或方法）的源码及其开始位置
+    let mut member = String::new();
+    let mut member_info = SourceInfo::default();
+
+    for (index, raw) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let (code, comment) = split_comment(raw, &mut in_block);
+        let code = code.trim();
+        if code.is_empty() {
+            match comment {
+                Some(text) => pending.push(text),
+                // 空行会打断注释块
+                None if raw.trim().is_empty() => pending.clear(),
+                None => {}
+            }
+            continue;
+        }
+
+        let doc_comment = if pending.is_empty() {
+            comment.unwrap_or_default()
+        } else {
+            pending.join("\n")
+        };
+        let doc_comment = doc_comment.trim().to_string();
+        pending.clear();
+        // 注释属于这一行开始的第一个定义
+        let mut line_info = Some(SourceInfo { line: line_no, comment: doc_comment });
+
+        if depth == 0 {
+            let mut words = code.split_whitespace();
+            let keyword = words.next().unwrap_or_default();
+            match keyword {
+                "struct" | "union" | "exception" | "enum" | "service" => {
+                    if let Some(name) = words.next() {
+                        let name = name.split('{').next().unwrap_or_default().to_string();
+                        result.insert(name.clone(), line_info.take().unwrap_or_default());
+                        scope = Some((keyword.to_string(), name));
+                    }
+                }
+                "typedef" | "const" => {
+                    if let Some(name) = definition_name(code, keyword) {
+                        result.insert(name, line_info.take().unwrap_or_default());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for c in code.chars() {
+            if depth >= 1 && member.trim().is_empty() && !c.is_whitespace() && !matches!(c, ',' | ';' | '}') {
+                member_info = line_info.take().unwrap_or(SourceInfo { line: line_no, comment: String::new() });
+            }
+            if let Some(q) = quote {
+                if c == q {
+                    quote = None;
+                }
+                member.push(c);
+                continue;
+            }
+            match c {
+                '{' => {
+                    if depth >= 1 {
+                        member.push(c);
+                    }
+                    depth += 1;
+                }
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        flush_member(&mut result, &mut member, &member_info, &scope);
+                        scope = None;
+                    } else {
+                        member.push(c);
+                    }
+                }
+                ',' | ';' if depth == 1 && paren_depth == 0 && angle_depth == 0 => {
+                    flush_member(&mut result, &mut member, &member_info, &scope);
+                }
+                _ if depth >= 1 => {
+                    match c {
+                        '(' => paren_depth += 1,
+                        ')' => paren_depth -= 1,
+                        '<' => angle_depth += 1,
+                        '>' => angle_depth -= 1,
+                        '"' | '\'' => quote = Some(c),
+                        _ => {}
+                    }
+                    member.push(c);
+                }
+                _ => {}
+            }
+        }
+        // 没有逗号分隔的成员在行末结束，括号未闭合时（多行的方法参数、注解）继续读取下一行
+        if depth == 1 && paren_depth == 0 && angle_depth == 0 {
+            flush_member(&mut result, &mut member, &member_info, &scope);
+        } else if depth >= 1 {
+            member.push(' ');
+        }
+    }
+    result
+}
+
+/// 记录读取完的成员（字段、枚举项或方法）并清空缓冲区
+fn flush_member(
+    result: &mut HashMap<String, SourceInfo>,
+    member: &mut String,
+    info: &SourceInfo,
+    scope: &Option<(String, String)>,
+) {
+    let code = member.trim();
+    if let Some((kind, parent)) = scope
+        && !code.is_empty()
+    {
+        let name = match kind.as_str() {
+            "enum" => enum_value_name(code),
+            "service" => method_name(code),
+            _ => field_name(code),
+        };
+        if let Some(name) = name {
+            result.insert(format!("{}.{}", parent, name), info.clone());
+        }
+    }
+    member.clear();
+}
+
+/// 将扫描得到的注释和行号写入 Document 中对应的定义
+pub fn apply_source_info(doc: &mut Document, content: &str) {
+    let source = scan_source(content);
+    let info = |key: &str| source.get(key).cloned().unwrap_or_default();
+    let apply_struct = |s: &mut Struct| {
+        SourceInfo { comment: s.comment, line: s.line } = info(&s.name);
+        for f in &mut s.fields {
+            SourceInfo { comment: f.comment, line: f.line } = info(&format!("{}.{}", s.name, f.name));
+        }
+    };
+    for s in &mut doc.structs {
+        apply_struct(s);
+    }
+    for e in &mut doc.enums {
+        SourceInfo { comment: e.comment, line: e.line } = info(&e.name);
+        for v in &mut e.values {
+            SourceInfo { comment: v.comment, line: v.line } = info(&format!("{}.{}", e.name, v.name));
+        }
+    }
+    for t in &mut doc.typedefs {
+        SourceInfo { comment: t.comment, line: t.line } = info(&t.name);
+    }
+    for c in &mut doc.constants {
+        SourceInfo { comment: c.comment, line: c.line } = info(&c.name);
+    }
+    for service in &mut doc.services {
+        SourceInfo { comment: service.comment, line: service.line } = info(&service.name);
+        for method in &mut service.methods {
+            SourceInfo { comment: method.comment, line: method.line } =
+                info(&format!("{}.{}", service.name, method.name));
+            apply_struct(&mut method.request);
+            apply_struct(&mut method.response);
+        }
+    }
+}
+
+/// 拆分一行中的代码和注释，`in_block` 记录是否处于跨行的 `/* */` 注释中
+fn split_comment(line: &str, in_block: &mut bool) -> (String, Option<String>) {
+    let mut code = String::new();
+    let mut comments = Vec::new();
+    let mut rest = line;
+    loop {
+        if *in_block {
+            match rest.find("*/") {
+                Some(end) => {
+                    comments.push(clean_comment(&rest[..end]));
+                    rest = &rest[end + 2..];
+                    *in_block = false;
+                }
+                None => {
+                    comments.push(clean_comment(rest));
+                    break;
+                }
+            }
+        } else {
+            match find_comment_start(rest) {
+                Some((pos, false)) => {
+                    code.push_str(&rest[..pos]);
+                    comments.push(clean_comment(&rest[pos..]));
+                    break;
+                }
+                Some((pos, true)) => {
+                    code.push_str(&rest[..pos]);
+                    rest = &rest[pos + 2..];
+                    *in_block = true;
+                }
+                None => {
+                    code.push_str(rest);
+                    break;
+                }
+            }
+        }
+    }
+    let comments: Vec<String> = comments.into_iter().filter(|c| !c.is_empty()).collect();
+    let comment = if comments.is_empty() { None } else { Some(comments.join("\n")) };
+    (code, comment)
+}
+
+/// 查找字符串字面量之外的第一个注释标记，返回其位置以及是否为块注释
+fn find_comment_start(text: &str) -> Option<(usize, bool)> {
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return Some((i, false)),
+            (None, '/') if text[i..].starts_with("//") => return Some((i, false)),
+            (None, '/') if text[i..].starts_with("/*") => return Some((i, true)),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 去掉注释标记，例如 `///`、`//`、`#`、`/**` 以及块注释每行开头的 `*`
+fn clean_comment(text: &str) -> String {
+    text.trim()
+        .trim_start_matches('/')
+        .trim_start_matches('#')
+        .trim_start_matches('*')
+        .trim_end_matches("*/")
+        .trim()
+        .to_string()
+}
+
+/// typedef 与 const 的名称：`typedef i64 UserId`、`const i32 MAX = 10`
+fn definition_name(code: &str, keyword: &str) -> Option<String> {
+    let body = code.strip_prefix(keyword)?;
+    let body = body.split('=').next()?;
+    last_identifier(body)
+}
+
+/// 结构体字段名：`1: required map<string, string> extra = {},` 中的 `extra`
+fn field_name(code: &str) -> Option<String> {
+    let (id, rest) = code.split_once(':')?;
+    id.trim().parse::<i32>().ok()?;
+    last_identifier(&cut_at_terminator(rest))
+}
+
+/// 枚举项名称：`RED = 1,` 中的 `RED`
+fn enum_value_name(code: &str) -> Option<String> {
+    let name = code
+        .split(|c: char| c == '=' || c == ',' || c == ';' || c == '(' || c.is_whitespace())
+        .next()?;
+    is_identifier(name).then(|| name.to_string())
+}
+
+/// 服务方法名：`GetItemResponse GetItem(1: GetItemRequest req)` 中的 `GetItem`
+fn method_name(code: &str) -> Option<String> {
+    let (head, _) = code.split_once('(')?;
+    // 单独一行的 `throws (...)` 不是方法
+    last_identifier(head).filter(|name| name != "throws")
+}
+
+/// 截取到最外层的 `=`、`(`、`,`、`;` 之前的部分
+fn cut_at_terminator(text: &str) -> String {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            '=' | '(' | ',' | ';' if depth == 0 => return text[..i].to_string(),
+            _ => {}
+        }
+    }
+    text.to_string()
+}
+
+fn last_identifier(text: &str) -> Option<String> {
+    let name = text.split_whitespace().last()?;
+    is_identifier(name).then(|| name.to_string())
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_source() {
+        let source = scan_source(
+            r#"namespace rs volo.example
+
+/**
+ * 商品信息
+ */
+struct Item {
+    // 商品 ID
+    1: required i64 id,
+    2: optional map<string, string> extra = {}, # 扩展字段
+}
+
+enum Status {
+    ON = 1, // 上架
+    OFF,
+}
+
+/// 商品服务
+service ItemService {
+    /// 查询商品
+    Item GetItem(
+        1: i64 id,
+    )
+}
+"#,
+        );
+        assert_eq!(source["Item"], SourceInfo { line: 6, comment: "商品信息".to_string() });
+        assert_eq!(source["Item.id"].comment, "商品 ID");
+        assert_eq!(source["Item.extra"], SourceInfo { line: 9, comment: "扩展字段".to_string() });
+        assert_eq!(source["Status.ON"].comment, "上架");
+        assert_eq!(source["Status.OFF"].line, 14);
+        assert_eq!(source["ItemService"].comment, "商品服务");
+        assert_eq!(source["ItemService.GetItem"], SourceInfo { line: 20, comment: "查询商品".to_string() });
+        assert!(!source.contains_key("ItemService.id"));
+    }
+
+    #[test]
+    fn test_scan_single_line_definitions() {
+        let source = scan_source(
+            r#"struct Tag { 1: required string name, 2: map<string, i32> scores = {"a,b": 1} }
+/// 颜色
+enum Color { RED = 1; GREEN = 2 }
+service TagService {
+    Tag GetTag(1: string name)
+        throws (1: Tag e),
+    // 删除
+    void Remove(1: string name)
+}
+"#,
+        );
+        assert_eq!(source["Tag"].line, 1);
+        assert_eq!(source["Tag.name"], SourceInfo { line: 1, comment: String::new() });
+        assert_eq!(source["Tag.scores"].line, 1);
+        assert_eq!(source["Color"].comment, "颜色");
+        assert_eq!(source["Color.GREEN"].line, 3);
+        assert_eq!(source["TagService.GetTag"].line, 5);
+        assert!(!source.contains_key("TagService.throws"));
+        assert_eq!(source["TagService.Remove"], SourceInfo { line: 8, comment: "删除".to_string() });
+        assert_eq!(source.len(), 9);
+    }
+}
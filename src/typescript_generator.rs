@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use crate::config::TypeScriptConfig;
+use crate::idl_parser::{parse_type, Document, Enum, Method, Service, Struct, TypeExpr};
+
+const INDENT: &str = "    ";
+
+/// 根据 Document 生成 TypeScript 类型定义
+///
+/// 结构体与异常生成 interface（非 required 字段为可选属性），union 生成可区分的联合类型，
+/// typedef 生成类型别名，每个服务生成一个 interface，方法返回 Promise。
+/// 被 include 文件中的类型（`base.Base`）放在同名的 namespace 中，引用写法与 IDL 保持一致。
+pub fn generate_typescript(doc: &Document, title: &str, options: &TypeScriptConfig) -> Result<String, String> {
+    if !matches!(options.enum_style.as_str(), "numeric" | "string") {
+        return Err(format!("不支持的枚举生成方式：{}（可选 numeric、string）", options.enum_style));
+    }
+    if !matches!(options.i64.as_str(), "number" | "bigint" | "string") {
+        return Err(format!("不支持的 i64 类型：{}（可选 number、bigint、string）", options.i64));
+    }
+
+    // key 为 namespace（入口文件中的定义为空字符串），value 为生成的定义
+    let mut blocks: BTreeMap<String, Vec<Vec<String>>> = BTreeMap::new();
+    let mut add = |name: &str, block: Vec<String>| {
+        let namespace = name.rsplit_once('.').map(|(ns, _)| ns).unwrap_or_default();
+        blocks.entry(namespace.to_string()).or_default().push(block);
+    };
+    for s in &doc.structs {
+        add(&s.name, struct_block(s, options));
+    }
+    for e in &doc.enums {
+        add(&e.name, enum_block(e, options));
+    }
+    for t in &doc.typedefs {
+        let mut block = doc_comment(&t.comment, &[]);
+        block.push(format!("export type {} = {};", local_name(&t.name), ts_type(&t.r#type, options)));
+        add(&t.name, block);
+    }
+    for service in &doc.services {
+        add(&service.name, service_block(service, options));
+    }
+
+    let mut lines = vec![format!("// 由 volodoc 根据 {}.thrift 生成，请勿手动修改", title)];
+    for (namespace, blocks) in &blocks {
+        lines.push(String::new());
+        if namespace.is_empty() {
+            lines.push(blocks.iter().map(|b| b.join("\n")).collect::<Vec<_>>().join("\n\n"));
+            continue;
+        }
+        lines.push(format!("export namespace {} {{", namespace));
+        let body: Vec<String> = blocks
+            .iter()
+            .map(|b| b.iter().map(|l| format!("{}{}", INDENT, l)).collect::<Vec<_>>().join("\n"))
+            .collect();
+        lines.push(body.join("\n\n"));
+        lines.push("}".to_string());
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+/// 将 IDL 类型转换为 TypeScript 类型
+pub fn ts_type(ty: &str, options: &TypeScriptConfig) -> String {
+    expr_type(&parse_type(ty), options)
+}
+
+fn expr_type(expr: &TypeExpr, options: &TypeScriptConfig) -> String {
+    match expr {
+        TypeExpr::Base(base) => match base.as_str() {
+            "bool" => "boolean".to_string(),
+            "byte" | "i8" | "i16" | "i32" | "double" => "number".to_string(),
+            "i64" => options.i64.clone(),
+            "void" => "void".to_string(),
+            // binary 在 JSON 中为 base64 字符串
+            _ => "string".to_string(),
+        },
+        TypeExpr::List(value) | TypeExpr::Set(value) => format!("{}[]", expr_type(value, options)),
+        // JSON 对象的 key 只能是字符串
+        TypeExpr::Map(_, value) => format!("Record<string, {}>", expr_type(value, options)),
+        TypeExpr::Named(name) => name.clone(),
+    }
+}
+
+fn struct_block(s: &Struct, options: &TypeScriptConfig) -> Vec<String> {
+    let mut block = doc_comment(&s.comment, &[]);
+    let name = local_name(&s.name);
+    if s.kind == "union" {
+        // 每个分支只允许出现一个字段，其余字段标记为 never，便于 TypeScript 区分
+        block.push(format!("export type {} =", name));
+        for (i, f) in s.fields.iter().enumerate() {
+            let mut members = vec![format!("{}: {}", f.name, ts_type(&f.r#type, options))];
+            members.extend(s.fields.iter().filter(|o| o.name != f.name).map(|o| format!("{}?: never", o.name)));
+            let end = if i + 1 == s.fields.len() { ";" } else { "" };
+            block.push(format!("{}| {{ {} }}{}", INDENT, members.join("; "), end));
+        }
+        if s.fields.is_empty() {
+            block.push(format!("{}never;", INDENT));
+        }
+        return block;
+    }
+
+    block.push(format!("export interface {} {{", name));
+    for f in &s.fields {
+        block.extend(doc_comment(&f.comment, &[]).into_iter().map(|l| format!("{}{}", INDENT, l)));
+        let optional = if f.attribute == "Required" { "" } else { "?" };
+        block.push(format!("{}{}{}: {};", INDENT, f.name, optional, ts_type(&f.r#type, options)));
+    }
+    block.push("}".to_string());
+    block
+}
+
+fn enum_block(e: &Enum, options: &TypeScriptConfig) -> Vec<String> {
+    let mut block = doc_comment(&e.comment, &[]);
+    let name = local_name(&e.name);
+    if options.enum_style == "string" {
+        let values: Vec<String> = e.values.iter().map(|v| format!("\"{}\"", v.name)).collect();
+        let values = if values.is_empty() { "never".to_string() } else { values.join(" | ") };
+        block.push(format!("export type {} = {};", name, values));
+        return block;
+    }
+    block.push(format!("export enum {} {{", name));
+    for v in &e.values {
+        block.extend(doc_comment(&v.comment, &[]).into_iter().map(|l| format!("{}{}", INDENT, l)));
+        block.push(format!("{}{} = {},", INDENT, v.name, v.value));
+    }
+    block.push("}".to_string());
+    block
+}
+
+fn service_block(service: &Service, options: &TypeScriptConfig) -> Vec<String> {
+    let mut block = doc_comment(&service.comment, &[]);
+    block.push(format!("export interface {} {{", service.name));
+    for method in &service.methods {
+        let throws: Vec<String> = method.throws.iter().map(|e| format!("@throws {{{}}}", e.r#type)).collect();
+        block.extend(doc_comment(&method.comment, &throws).into_iter().map(|l| format!("{}{}", INDENT, l)));
+        block.push(format!("{}{};", INDENT, method_signature(method, options)));
+    }
+    block.push("}".to_string());
+    block
+}
+
+/// 方法签名，例如 `GetItem(req: GetItemRequest): Promise<Item>`，每个 IDL 参数对应一个同名参数
+fn method_signature(method: &Method, options: &TypeScriptConfig) -> String {
+    let arguments: Vec<String> =
+        method.arguments.iter().map(|a| format!("{}: {}", a.name, ts_type(&a.r#type, options))).collect();
+    let response = if method.response.name.is_empty() {
+        "void".to_string()
+    } else {
+        ts_type(&method.response.name, options)
+    };
+    format!("{}({}): Promise<{}>", method.name, arguments.join(", "), response)
+}
+
+/// 生成 JSDoc 注释，没有注释内容时返回空列表
+fn doc_comment(comment: &str, tags: &[String]) -> Vec<String> {
+    let lines: Vec<&str> = comment.lines().chain(tags.iter().map(|t| t.as_str())).collect();
+    match lines.as_slice() {
+        [] => Vec::new(),
+        [line] => vec![format!("/** {} */", line)],
+        _ => {
+            let mut block = vec!["/**".to_string()];
+            block.extend(lines.iter().map(|l| format!(" * {}", l)));
+            block.push(" */".to_string());
+            block
+        }
+    }
+}
+
+/// 去掉 namespace 前缀后的类型名，例如 `base.Base` 返回 `Base`
+fn local_name(name: &str) -> &str {
+    name.rsplit_once('.').map(|(_, n)| n).unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl_parser::{build_document, parse_idl};
+    use crate::source_map::apply_source_info;
+
+    const SAMPLE_THRIFT: &str = r#"
+        enum Status { ON = 1, OFF = 2 }
+
+        union Key { 1: i64 id, 2: string slug }
+
+        /// 商品
+        struct Item {
+            1: required i64 id,
+            2: optional list<string> tags,
+            3: optional map<i32, Status> flags,
+        }
+
+        exception NotFound { 1: string message }
+
+        service ItemService {
+            /// 查询商品
+            Item GetItem (1: Key key) throws (1: NotFound e),
+            void Ping (),
+            string Search (1: Key key, 2: string page, 3: i64 extra),
+        }
+    "#;
+
+    #[test]
+    fn test_generate_typescript() {
+        let mut doc = build_document(&parse_idl(SAMPLE_THRIFT).unwrap());
+        apply_source_info(&mut doc, SAMPLE_THRIFT);
+        let ts = generate_typescript(&doc, "item", &TypeScriptConfig::default()).unwrap();
+
+        assert!(ts.contains("/** 商品 */\nexport interface Item {\n    id: number;\n    tags?: string[];\n    flags?: Record<string, Status>;\n}"));
+        assert!(ts.contains("export type Key =\n    | { id: number; slug?: never }\n    | { slug: string; id?: never };"));
+        assert!(ts.contains("export enum Status {\n    ON = 1,\n    OFF = 2,\n}"));
+        assert!(ts.contains("    /**\n     * 查询商品\n     * @throws {NotFound}\n     */\n    GetItem(key: Key): Promise<Item>;"));
+        assert!(ts.contains("    Search(key: Key, page: string, extra: number): Promise<string>;"));
+        assert!(ts.contains("    Ping(): Promise<void>;"));
+
+        let options = TypeScriptConfig { enum_style: "string".to_string(), i64: "bigint".to_string() };
+        let ts = generate_typescript(&doc, "item", &options).unwrap();
+        assert!(ts.contains("export type Status = \"ON\" | \"OFF\";"));
+        assert!(ts.contains("    id: bigint;"));
+
+        let options = TypeScriptConfig { i64: "long".to_string(), ..Default::default() };
+        assert!(generate_typescript(&doc, "item", &options).is_err());
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// 基于轮询的文件监听器，记录各文件的修改时间，通过对比快照找出发生变化的文件
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    snapshot: HashMap<PathBuf, SystemTime>,
+}
+
+impl Watcher {
+    /// 监听给定的文件或目录（目录会递归扫描），创建时记录一次初始快照
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let mut watcher = Watcher {
+            paths,
+            snapshot: HashMap::new(),
+        };
+        watcher.snapshot = watcher.scan();
+        watcher
+    }
+
+    /// 重新扫描并返回自上次调用以来新增、修改或删除的文件
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let current = self.scan();
+        let mut changed: Vec<PathBuf> = current
+            .iter()
+            .filter(|(path, mtime)| self.snapshot.get(*path) != Some(mtime))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.extend(
+            self.snapshot
+                .keys()
+                .filter(|path| !current.contains_key(*path))
+                .cloned(),
+        );
+        changed.sort();
+        self.snapshot = current;
+        changed
+    }
+
+    fn scan(&self) -> HashMap<PathBuf, SystemTime> {
+        let mut files = HashMap::new();
+        for path in &self.paths {
+            scan_path(path, &mut files);
+        }
+        files
+    }
+}
+
+fn scan_path(path: &Path, files: &mut HashMap<PathBuf, SystemTime>) {
+    let Ok(meta) = fs::metadata(path) else {
+        return;
+    };
+    if meta.is_dir() {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                scan_path(&entry.path(), files);
+            }
+        }
+    } else if let Ok(mtime) = meta.modified() {
+        files.insert(path.to_path_buf(), mtime);
+    }
+}